@@ -1,4 +1,6 @@
 var cacheName = 'sv-raid-lookup-pwa';
+var spriteCacheName = 'sv-raid-lookup-sprites';
+var fontCacheName = 'sv-raid-lookup-fonts';
 var filesToCache = [
   './',
   './index.html',
@@ -15,11 +17,42 @@ self.addEventListener('install', function (e) {
   );
 });
 
-/* Serve cached content when offline */
+function isSprite(url) {
+  return url.indexOf('/sprites/') !== -1 && url.endsWith('.png');
+}
+
+function isFont(url) {
+  return url.indexOf('/noto-cjk/') !== -1 && url.endsWith('.otf');
+}
+
+/* Cache downloaded sprites and fonts are kept in, or null for everything else */
+function runtimeCache(url) {
+  if (isSprite(url)) {
+    return spriteCacheName;
+  }
+  if (isFont(url)) {
+    return fontCacheName;
+  }
+  return null;
+}
+
+/* Serve cached content when offline, keeping every sprite and font that gets downloaded */
 self.addEventListener('fetch', function (e) {
   e.respondWith(
     caches.match(e.request).then(function (response) {
-      return response || fetch(e.request);
+      var name = runtimeCache(e.request.url);
+      if (response || !name) {
+        return response || fetch(e.request);
+      }
+      return fetch(e.request).then(function (response) {
+        if (response.ok) {
+          var copy = response.clone();
+          caches.open(name).then(function (cache) {
+            cache.put(e.request, copy);
+          });
+        }
+        return response;
+      });
     })
   );
 });
@@ -0,0 +1,23 @@
+#!/bin/sh
+# Copies the translated name tables from a PKHeX checkout, whose text files use the same order
+# as the English tables in sv_raid_reader.
+#
+# Usage: resources/names/import_pkhex.sh path/to/PKHeX
+set -eu
+
+pkhex=$1
+out=$(dirname "$0")
+
+for lang in ja fr de es it ko zh; do
+    for table in Species:species Abilities:abilities Items:items Moves:moves Natures:natures \
+        Types:types; do
+        name="text_${table%%:*}_${lang}.txt"
+        src=$(find "$pkhex" -name "$name" | head -n 1)
+        if [ -z "$src" ]; then
+            echo "$name not found, keeping $lang/${table#*:}.txt" >&2
+            continue
+        fi
+        # Drop the byte order mark and Windows line endings.
+        sed -e '1s/^\xEF\xBB\xBF//' -e 's/\r$//' "$src" >"$out/$lang/${table#*:}.txt"
+    done
+done
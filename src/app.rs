@@ -1,53 +1,490 @@
-use crate::details_window::DetailsWindow;
+use crate::compare::Comparison;
+use crate::damage::DamageCalc;
+use crate::deep_link::DeepLink;
+use crate::details_window::{mightiest_mark_notes, DetailsTab, DetailsWindow, EventGroup};
+use crate::encounter_index::EncounterIndex;
+use crate::event_data::{EventData, EventFile, LoadError};
+use crate::filters::EncounterFilter;
+use crate::fonts::load_font;
+use crate::i18n::tr;
 use crate::is_mobile;
 use crate::mobile_bar::mobile_top_bar;
+use crate::names::{self, Language, LANGUAGE_KEY};
+use crate::priority::{event_chances, EventPriority};
+use crate::progress::StoryProgress;
+use crate::query::Query;
+use crate::rewards::{encounter_rewards, Reward};
+use crate::showdown::showdown_set;
 use crate::side_panel::draw_side_panel;
-use eframe::egui::{Context, DroppedFile, Visuals};
+use crate::species_page::SpeciesPage;
+use crate::sprites::SpriteCache;
+use crate::teams::{TeamRoster, STORAGE_KEY};
+use crate::version::{spawn_chances, GameVersion, SpawnChance};
+use eframe::egui::{Color32, Context, DroppedFile, RichText, Visuals};
 use eframe::{egui, App, CreationContext, Frame};
 use std::collections::HashMap;
-#[cfg(not(target_arch = "wasm32"))]
-use std::fs::File;
-#[cfg(not(target_arch = "wasm32"))]
-use std::io::Read;
+use std::sync::atomic::AtomicUsize;
 use std::sync::{Arc, Mutex};
-use sv_raid_reader::{ItemTable, RaidEncounter, DIFFICULTY_06, SPECIES};
+use sv_raid_reader::{
+    ItemTable, RaidEncounter, DIFFICULTY_01, DIFFICULTY_02, DIFFICULTY_03, DIFFICULTY_04,
+    DIFFICULTY_05, DIFFICULTY_06, FIXED_ITEMS, LOTTERY_ITEMS, SPECIES,
+};
+
+/// 7★ raids only exist as events, so that level has no base game table.
+pub const MAX_STARS: u8 = 7;
+
+pub const WEB_URL: &str = "https://icekriim.github.io/svraid_lookup/";
+
+/// Identifies where an encounter came from so it can be looked up again later.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EncounterSource {
+    Base { stars: u8, index: usize },
+    Event { index: usize },
+}
+
+/// An event encounter from a shared link, selected once the event data has loaded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PendingEvent {
+    pub index: usize,
+    /// `version_no` of the event the link was made for.
+    pub event_id: Option<i32>,
+}
 
 pub struct SVRaidLookup {
     pub star_level: u8,
+    pub all_stars: bool,
     pub species_filter: String,
-    pub encounters: Vec<RaidEncounter>,
+    pub query: Query,
+    pub query_error: Option<String>,
+    pub filter: EncounterFilter,
+    pub game_version: Option<GameVersion>,
+    pub progress: StoryProgress,
+    pub seed: String,
+    pub encounters: Vec<(EncounterSource, RaidEncounter)>,
     pub event_encounters: Arc<Mutex<Vec<RaidEncounter>>>,
+    pub event_version: Option<String>,
+    pub event_priority: Arc<Mutex<Option<EventPriority>>>,
     pub fixed_event_item: Arc<Mutex<ItemTable>>,
     pub lottery_event_items: Arc<Mutex<ItemTable>>,
+    /// Bumped whenever event data finishes loading so the encounter index is rebuilt.
+    pub data_version: Arc<AtomicUsize>,
+    pub load_errors: Arc<Mutex<Vec<LoadError>>>,
+    pub encounter_index: EncounterIndex,
     pub details_window: Option<DetailsWindow>,
+    pub details_tab: DetailsTab,
+    /// Encounters opened in their own floating windows.
+    pub windows: Vec<(EncounterSource, DetailsWindow)>,
+    pub comparison: Comparison,
+    pub species_page: Option<SpeciesPage>,
+    pub damage_calc: DamageCalc,
+    pub teams: TeamRoster,
+    pub sprites: SpriteCache,
+    pub selected: Option<EncounterSource>,
+    pub pending_event: Option<PendingEvent>,
+    pub link_base: String,
 }
 
 impl Default for SVRaidLookup {
     fn default() -> Self {
         Self {
             star_level: 6,
+            all_stars: false,
             species_filter: String::new(),
-            encounters: {
-                let mut enc = DIFFICULTY_06.to_vec();
-                enc.sort_by_key(|e| SPECIES[e.species as usize]);
-                enc
-            },
+            query: Query::default(),
+            query_error: None,
+            filter: EncounterFilter::default(),
+            game_version: None,
+            progress: StoryProgress::SixStars,
+            seed: String::new(),
+            encounters: base_encounters(6),
             event_encounters: Arc::new(Mutex::new(vec![])),
+            event_version: None,
+            event_priority: Arc::new(Mutex::new(None)),
             fixed_event_item: Arc::new(Mutex::new(ItemTable(HashMap::default()))),
             lottery_event_items: Arc::new(Mutex::new(ItemTable(HashMap::default()))),
+            data_version: Arc::new(AtomicUsize::new(0)),
+            load_errors: Arc::new(Mutex::new(vec![])),
+            encounter_index: EncounterIndex::default(),
             details_window: None,
+            details_tab: DetailsTab::Overview,
+            windows: vec![],
+            comparison: Comparison::default(),
+            species_page: None,
+            damage_calc: DamageCalc::default(),
+            teams: TeamRoster::default(),
+            sprites: SpriteCache::default(),
+            selected: None,
+            pending_event: None,
+            link_base: WEB_URL.to_string(),
         }
     }
 }
 
+pub fn difficulty_table(star_level: u8) -> &'static [RaidEncounter] {
+    match star_level {
+        2 => &DIFFICULTY_02[..],
+        3 => &DIFFICULTY_03[..],
+        4 => &DIFFICULTY_04[..],
+        5 => &DIFFICULTY_05[..],
+        6 => &DIFFICULTY_06[..],
+        7 => &[],
+        _ => &DIFFICULTY_01[..],
+    }
+}
+
+/// Base game encounters for a star level, sorted by species name.
+pub fn base_encounters(star_level: u8) -> Vec<(EncounterSource, RaidEncounter)> {
+    let mut enc = difficulty_table(star_level)
+        .iter()
+        .enumerate()
+        .map(|(index, e)| {
+            (
+                EncounterSource::Base {
+                    stars: star_level,
+                    index,
+                },
+                *e,
+            )
+        })
+        .collect::<Vec<_>>();
+    enc.sort_by_key(|(_, e)| names::english(&SPECIES, e.species as usize));
+    enc
+}
+
 impl SVRaidLookup {
     pub fn new(cc: &CreationContext) -> Self {
         cc.egui_ctx.set_visuals(Visuals::default());
-        Default::default()
+        let mut app = Self::default();
+        if let Some(storage) = cc.storage {
+            if let Some(teams) = storage.get_string(STORAGE_KEY) {
+                app.teams = TeamRoster::load(&teams);
+            }
+            if let Some(language) = storage
+                .get_string(LANGUAGE_KEY)
+                .and_then(|code| Language::from_code(&code))
+            {
+                names::set_language(language);
+                load_font(language, &cc.egui_ctx);
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let location = &cc.integration_info.web_info.location;
+            app.link_base = location
+                .url
+                .split('#')
+                .next()
+                .unwrap_or(WEB_URL)
+                .to_string();
+            let link = if location.hash.len() > 1 {
+                DeepLink::parse(&location.hash)
+            } else {
+                DeepLink::parse(&location.query)
+            };
+            if let Some(link) = link {
+                app.apply_link(link, &cc.egui_ctx);
+            }
+        }
+
+        app
+    }
+
+    pub fn set_star_level(&mut self, star_level: u8) {
+        self.star_level = star_level;
+        self.all_stars = false;
+        self.refresh_encounters();
+    }
+
+    /// Re-parses the search box. The last valid query stays active while the input has errors.
+    pub fn update_query(&mut self) {
+        match Query::parse(&self.species_filter) {
+            Ok(query) => {
+                self.query = query;
+                self.query_error = None;
+            }
+            Err(error) => self.query_error = Some(error.0),
+        }
+    }
+
+    /// Reloads the base game encounters for the current star selection.
+    pub fn refresh_encounters(&mut self) {
+        self.encounters = if self.all_stars {
+            (1..=MAX_STARS).flat_map(base_encounters).collect()
+        } else {
+            base_encounters(self.star_level)
+        };
+    }
+
+    pub fn select(&mut self, source: EncounterSource, encounter: &RaidEncounter, ctx: &Context) {
+        self.selected = Some(source);
+        self.species_page = None;
+        let spawn_chances = self.spawn_chances(source, encounter);
+        let rewards = self.rewards(source, encounter);
+        let mut details = DetailsWindow::new(encounter, &spawn_chances, rewards);
+        // Start loading the sprite right away rather than on the next frame.
+        self.sprites.get(details.sprite, ctx);
+        if let EncounterSource::Event { .. } = source {
+            let id = encounter.delivery_group_id;
+            details.event_group = Some(EventGroup {
+                id,
+                chance: self
+                    .event_priority
+                    .lock()
+                    .ok()
+                    .and_then(|priority| *priority)
+                    .map(|priority| priority.group_chance(id)),
+            });
+        }
+        self.details_window = Some(details);
+    }
+
+    /// Loads a font for the new language and rebuilds the open details and species page so
+    /// they show names in it.
+    pub fn language_changed(&mut self, ctx: &Context) {
+        load_font(names::language(), ctx);
+        let species_page_open = self.species_page.is_some();
+        if let Some(source) = self.selected {
+            if let Some(encounter) = self.lookup(source) {
+                self.select(source, &encounter, ctx);
+                if species_page_open {
+                    self.species_page =
+                        Some(SpeciesPage::new(self, encounter.species, encounter.form));
+                }
+            }
+        }
+    }
+
+    pub fn lookup(&self, source: EncounterSource) -> Option<RaidEncounter> {
+        match source {
+            EncounterSource::Base { stars, index } => difficulty_table(stars).get(index).copied(),
+            EncounterSource::Event { index } => self
+                .event_encounters
+                .lock()
+                .ok()
+                .and_then(|events| events.get(index).copied()),
+        }
+    }
+
+    /// Every base game encounter across all star levels followed by the loaded event encounters.
+    pub fn all_encounters(&self) -> Vec<(EncounterSource, RaidEncounter)> {
+        let mut all = (1..=MAX_STARS)
+            .flat_map(base_encounters)
+            .collect::<Vec<_>>();
+        if let Ok(events) = self.event_encounters.lock() {
+            all.extend(
+                events
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, e)| e.species != 0)
+                    .map(|(index, e)| (EncounterSource::Event { index }, *e)),
+            );
+        }
+        all
+    }
+
+    pub fn spawn_chances(
+        &self,
+        source: EncounterSource,
+        encounter: &RaidEncounter,
+    ) -> Vec<SpawnChance> {
+        match source {
+            EncounterSource::Base { stars, .. } => {
+                spawn_chances(encounter, difficulty_table(stars))
+            }
+            EncounterSource::Event { .. } => {
+                match (self.event_encounters.lock(), self.event_priority.lock()) {
+                    (Ok(events), Ok(priority)) => {
+                        event_chances(encounter, &events, priority.as_ref())
+                    }
+                    _ => vec![],
+                }
+            }
+        }
+    }
+
+    pub fn rewards(&self, source: EncounterSource, encounter: &RaidEncounter) -> Vec<Reward> {
+        match source {
+            EncounterSource::Base { .. } => {
+                encounter_rewards(encounter, &FIXED_ITEMS, &LOTTERY_ITEMS)
+            }
+            EncounterSource::Event { .. } => {
+                match (
+                    self.fixed_event_item.lock(),
+                    self.lottery_event_items.lock(),
+                ) {
+                    (Ok(fixed), Ok(lottery)) => encounter_rewards(encounter, &fixed, &lottery),
+                    _ => vec![],
+                }
+            }
+        }
+    }
+
+    pub fn share_link(&self) -> String {
+        let link = DeepLink {
+            star_level: self.star_level,
+            all_stars: self.all_stars,
+            species_filter: self.species_filter.clone(),
+            encounter: self.selected,
+            event_version: self.event_version.clone(),
+            event_id: self.event_id(),
+            seed: u32::from_str_radix(self.seed.trim(), 16).ok(),
+        };
+        format!("{}#{}", self.link_base, link.to_fragment())
+    }
+
+    pub fn apply_link(&mut self, link: DeepLink, ctx: &Context) {
+        self.set_star_level(link.star_level);
+        if link.all_stars {
+            self.all_stars = true;
+            self.refresh_encounters();
+        }
+        self.species_filter = link.species_filter;
+        self.update_query();
+        if let Some(seed) = link.seed {
+            self.seed = format!("{:08X}", seed);
+        }
+        match link.encounter {
+            Some(source @ EncounterSource::Base { .. }) => {
+                if let Some(encounter) = self.lookup(source) {
+                    self.select(source, &encounter, ctx);
+                }
+            }
+            Some(EncounterSource::Event { index }) => {
+                self.pending_event = Some(PendingEvent {
+                    index,
+                    event_id: link.event_id,
+                });
+                self.load_event_data(link.event_version.as_deref().unwrap_or("latest"), ctx);
+            }
+            None => {}
+        }
+    }
+
+    /// `version_no` of the loaded event, which identifies it even when it was loaded as `latest`.
+    pub fn event_id(&self) -> Option<i32> {
+        self.event_priority
+            .lock()
+            .ok()
+            .and_then(|priority| priority.as_ref().map(|priority| priority.version_no))
+    }
+
+    /// Selects the encounter a link pointed at once its event has loaded. The link is dropped
+    /// when loading fails, when a different event loaded or when the encounter isn't there, so
+    /// a later load can't select something unrelated.
+    fn resolve_pending_event(&mut self, pending: PendingEvent, ctx: &Context) {
+        let data = self.event_data();
+        if data.failed(EventFile::Encounters) {
+            self.pending_event = None;
+            return;
+        }
+        let loaded = self
+            .event_encounters
+            .lock()
+            .map_or(false, |events| !events.is_empty());
+        let event_id = self.event_id();
+        let waiting_for_id =
+            pending.event_id.is_some() && event_id.is_none() && !data.failed(EventFile::Priority);
+        if !loaded || waiting_for_id {
+            return;
+        }
+
+        self.pending_event = None;
+        if let (Some(linked), Some(loaded)) = (pending.event_id, event_id) {
+            if linked != loaded {
+                data.report(LoadError::OutdatedLink { linked, loaded });
+                return;
+            }
+        }
+        let source = EncounterSource::Event {
+            index: pending.index,
+        };
+        match self.lookup(source) {
+            Some(encounter) if encounter.species != 0 => self.select(source, &encounter, ctx),
+            _ => data.report(LoadError::MissingEncounter {
+                index: pending.index,
+            }),
+        }
+    }
+
+    /// Shared handles to the event tables for loading into them.
+    pub fn event_data(&self) -> EventData {
+        EventData {
+            encounters: self.event_encounters.clone(),
+            priority: self.event_priority.clone(),
+            fixed_items: self.fixed_event_item.clone(),
+            lottery_items: self.lottery_event_items.clone(),
+            version: self.data_version.clone(),
+            errors: self.load_errors.clone(),
+        }
+    }
+
+    pub fn load_event_data(&mut self, version: &str, ctx: &Context) {
+        self.event_version = Some(version.to_string());
+        let base_url = format!("https://citrusbolt.net/bcat/v/{}/raid/files", version);
+
+        for file in EventFile::ALL {
+            let request = ehttp::Request::get(format!("{}/{}", base_url, file.name()));
+            let data = self.event_data();
+            let ctx = ctx.clone();
+            ehttp::fetch(request, move |response| {
+                let result = match response {
+                    Ok(response) if response.ok => data.load(file, &response.bytes),
+                    Ok(response) => Err(LoadError::Download {
+                        file,
+                        message: format!("{} {}", response.status, response.status_text),
+                    }),
+                    Err(message) => Err(LoadError::Download { file, message }),
+                };
+                if let Err(error) = result {
+                    data.report(error);
+                }
+                ctx.request_repaint();
+            });
+        }
+    }
+
+    /// Loads a file dropped onto the window, telling the event files apart by their size.
+    fn load_dropped_file(&mut self, file: &DroppedFile) -> Result<(), LoadError> {
+        let name = file
+            .path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| file.name.clone());
+        let bytes = match (file.bytes.as_ref(), file.path.as_ref()) {
+            (Some(bytes), _) => bytes.to_vec(),
+            #[cfg(not(target_arch = "wasm32"))]
+            (None, Some(path)) => std::fs::read(path).map_err(|error| LoadError::Read {
+                file: name.clone(),
+                message: error.to_string(),
+            })?,
+            _ => {
+                return Err(LoadError::Read {
+                    file: name,
+                    message: "no contents".to_string(),
+                })
+            }
+        };
+
+        let event_file =
+            EventFile::from_size(bytes.len()).ok_or_else(|| LoadError::UnknownFile {
+                file: name,
+                size: bytes.len(),
+            })?;
+        self.event_data().load(event_file, &bytes)?;
+        if event_file == EventFile::Encounters {
+            self.event_version = None;
+        }
+        Ok(())
     }
 }
 
 impl App for SVRaidLookup {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        storage.set_string(STORAGE_KEY, self.teams.save());
+        storage.set_string(LANGUAGE_KEY, names::language().code().to_string());
+    }
+
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
         if is_mobile(ctx) {
             mobile_top_bar(self, ctx);
@@ -55,90 +492,187 @@ impl App for SVRaidLookup {
             draw_side_panel(self, ctx);
         }
 
-        if let Some(details) = self.details_window.as_ref() {
+        if let Some(pending) = self.pending_event {
+            self.resolve_pending_event(pending, ctx);
+        }
+
+        let share_link = self.share_link();
+        let mut open_species_page = false;
+        let mut open_window = false;
+        let mut add_to_comparison = false;
+        if let Some(page) = self.species_page.as_ref() {
+            let mut close = false;
+            egui::CentralPanel::default().show(ctx, |ui| {
+                close = page.show(ui);
+            });
+            if close {
+                self.species_page = None;
+            }
+        } else if self.comparison.open {
+            let mut close = false;
+            egui::CentralPanel::default().show(ctx, |ui| {
+                close = self.comparison.show(ui, &self.sprites, ctx);
+            });
+            if close {
+                self.comparison.open = false;
+            }
+        } else if let Some(details) = self.details_window.as_mut() {
             egui::CentralPanel::default().show(ctx, |ui| {
                 ui.horizontal(|ui| {
-                    let image = details.image.lock().unwrap();
-                    if let Some(image) = image.as_ref() {
-                        image.show(ui);
+                    for tab in DetailsTab::ALL {
+                        ui.selectable_value(&mut self.details_tab, tab, tr(tab.name()));
                     }
-                    ui.vertical(|ui| {
-                        ui.label(&details.base_type);
-                        ui.label(&details.base_stats);
-                        egui::Grid::new("stars_levels").show(ui, |ui| {
-                            ui.label(&details.level);
-                            ui.label(&details.hp);
-                            ui.end_row();
-                            ui.label(&details.stars);
-                            ui.label(&details.gender);
-                            ui.end_row();
-                            ui.label(&details.nature);
-                            ui.label(&details.ability);
-                        });
-                    });
                 });
-                ui.add_space(5.0);
                 ui.separator();
-                ui.add_space(5.0);
-                egui::ScrollArea::both().show(ui, |_ui| {});
-            });
-        }
-
-        if !ctx.input().raw.dropped_files.is_empty() {
-            let files: Vec<DroppedFile> = ctx.input().raw.dropped_files.clone();
-
-            for file in files.iter() {
-                #[cfg(not(target_arch = "wasm32"))]
-                if let Some(path) = file.path.as_ref() {
-                    if let Ok(mut file) = File::open(path) {
-                        let mut buf = Vec::new();
-                        file.read_to_end(&mut buf).unwrap();
-
-                        if buf.len() == 30000 {
-                            if let Ok(raid_table_array) = sv_raid_reader::delivery_enemy_table_generated::root_as_delivery_raid_enemy_table_array(&buf) {
-                                if let Ok(mut event_encounters) = self.event_encounters.lock() {
-                                    *event_encounters = raid_table_array.values().into_iter().map(|t| t.raidEnemyInfo().into()).collect::<Vec<_>>();
-                                }
-                            }
-                        } else if buf.len() == 27456 {
-                            if let Ok(fixed_item_table) = sv_raid_reader::raid_fixed_reward_item_generated::root_as_raid_fixed_reward_item_array(&buf) {
-                                if let Ok(mut fixed_event_items) = self.fixed_event_item.lock() {
-                                    *fixed_event_items = fixed_item_table.into();
-                                }
-                            }
-                        } else if buf.len() == 53464 {
-                            if let Ok(lottery_item_table) = sv_raid_reader::raid_lottery_reward_item_generated::root_as_raid_lottery_reward_item_array(&buf) {
-                                if let Ok(mut lottery_event_items) = self.lottery_event_items.lock() {
-                                    *lottery_event_items = lottery_item_table.into();
-                                }
+                egui::ScrollArea::both().show(ui, |ui| match self.details_tab {
+                    DetailsTab::Overview => {
+                        ui.horizontal(|ui| {
+                            details.sprite_ui(ui, &self.sprites, ctx);
+                            ui.vertical(|ui| {
+                                details.overview_ui(ui);
+                                ui.horizontal(|ui| {
+                                    if ui.button(tr("Copy Showdown Set")).clicked() {
+                                        ui.output().copied_text = showdown_set(&details.details);
+                                    }
+                                    if ui.button(tr("Copy Link")).clicked() {
+                                        ui.output().copied_text = share_link.clone();
+                                    }
+                                    if ui.button(tr("All Raids For Species")).clicked() {
+                                        open_species_page = true;
+                                    }
+                                });
+                                ui.horizontal(|ui| {
+                                    if ui.button(tr("Open in Window")).clicked() {
+                                        open_window = true;
+                                    }
+                                    let can_add = self.selected.map_or(false, |source| {
+                                        !self.comparison.contains(source)
+                                            && !self.comparison.is_full()
+                                    });
+                                    if ui
+                                        .add_enabled(
+                                            can_add,
+                                            egui::Button::new(tr("Add to Compare")),
+                                        )
+                                        .clicked()
+                                    {
+                                        add_to_comparison = true;
+                                    }
+                                    if self.comparison.count() > 0
+                                        && ui
+                                            .button(format!(
+                                                "{} ({})",
+                                                tr("Compare"),
+                                                self.comparison.count()
+                                            ))
+                                            .clicked()
+                                    {
+                                        self.comparison.open = true;
+                                    }
+                                });
+                            });
+                        });
+                        let mightiest_mark = mightiest_mark_notes(&details.details);
+                        if !mightiest_mark.is_empty() {
+                            ui.add_space(5.0);
+                            ui.label(
+                                RichText::new(format!("7★ {}", tr("Mightiest Mark Raid")))
+                                    .strong()
+                                    .color(Color32::GOLD),
+                            );
+                            for note in mightiest_mark.iter() {
+                                ui.label(note);
                             }
                         }
                     }
-                }
-
-                #[cfg(target_arch = "wasm32")]
-                if let Some(bytes) = file.bytes.as_ref() {
-                    let buf = bytes.to_vec();
-                    if buf.len() == 30000 {
-                        if let Ok(raid_table_array) = sv_raid_reader::delivery_enemy_table_generated::root_as_delivery_raid_enemy_table_array(&buf) {
-                            if let Ok(mut event_encounters) = self.event_encounters.lock() {
-                                *event_encounters = raid_table_array.values().into_iter().map(|t| t.raidEnemyInfo().into()).collect::<Vec<_>>();
-                            }
-                        }
-                    } else if buf.len() == 27456 {
-                        if let Ok(fixed_item_table) = sv_raid_reader::raid_fixed_reward_item_generated::root_as_raid_fixed_reward_item_array(&buf) {
-                            if let Ok(mut fixed_event_items) = self.fixed_event_item.lock() {
-                                *fixed_event_items = fixed_item_table.into();
-                            }
-                        }
-                    } else if buf.len() == 53464 {
-                        if let Ok(lottery_item_table) = sv_raid_reader::raid_lottery_reward_item_generated::root_as_raid_lottery_reward_item_array(&buf) {
-                            if let Ok(mut lottery_event_items) = self.lottery_event_items.lock() {
-                                *lottery_event_items = lottery_item_table.into();
+                    DetailsTab::Stats => {
+                        details.base_stats_ui(ui);
+                        ui.add_space(5.0);
+                        details.stat_calculator.ui(ui);
+                    }
+                    DetailsTab::Moves => {
+                        details.moves_ui(ui);
+                        ui.add_space(5.0);
+                        ui.strong(tr("Extra Actions"));
+                        details.extra_actions_ui(ui);
+                        ui.add_space(5.0);
+                        egui::CollapsingHeader::new(tr("Type Matchups"))
+                            .show(ui, |ui| details.matchup.ui(ui));
+                        egui::CollapsingHeader::new(tr("Team")).show(ui, |ui| {
+                            self.teams
+                                .ui(ui, &details.stat_calculator, &details.matchup)
+                        });
+                        egui::CollapsingHeader::new(tr("Damage Calc")).show(ui, |ui| {
+                            self.damage_calc.ui(
+                                ui,
+                                &details.stat_calculator,
+                                &details.defense_types,
+                            )
+                        });
+                    }
+                    DetailsTab::Rewards => details.rewards_ui(ui),
+                    DetailsTab::Probability => details.probability_ui(ui, self.progress),
+                    DetailsTab::Seed => {
+                        ui.horizontal(|ui| {
+                            ui.label(tr("Seed:"));
+                            egui::TextEdit::singleline(&mut self.seed)
+                                .hint_text(tr("optional, hex"))
+                                .desired_width(80.0)
+                                .show(ui);
+                            if ui.button(tr("Copy Link")).clicked() {
+                                ui.output().copied_text = share_link.clone();
                             }
-                        }
+                        });
+                        ui.add_space(5.0);
+                        details.seed_ui(ui, &self.seed);
                     }
-                }
+                });
+            });
+        }
+
+        if open_species_page {
+            if let Some(encounter) = self.selected.and_then(|source| self.lookup(source)) {
+                self.species_page = Some(SpeciesPage::new(self, encounter.species, encounter.form));
+            }
+        }
+        if let (Some(source), Some(details)) = (self.selected, self.details_window.as_ref()) {
+            if open_window && !self.windows.iter().any(|(s, _)| *s == source) {
+                self.windows.push((source, details.clone()));
+            }
+            if add_to_comparison {
+                self.comparison.add(source, details.details.clone());
+            }
+        }
+
+        let sprites = &self.sprites;
+        let progress = self.progress;
+        self.windows.retain_mut(|(source, details)| {
+            let mut open = true;
+            egui::Window::new(details.title())
+                .id(egui::Id::new(("details", *source)))
+                .open(&mut open)
+                .default_width(360.0)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        details.sprite_ui(ui, sprites, ctx);
+                        ui.vertical(|ui| {
+                            details.overview_ui(ui);
+                            details.probability_ui(ui, progress);
+                        });
+                    });
+                    egui::CollapsingHeader::new(tr("Stats"))
+                        .show(ui, |ui| details.stat_calculator.ui(ui));
+                    egui::CollapsingHeader::new(tr("Extra Actions")).show(ui, |ui| {
+                        details.extra_actions_ui(ui);
+                    });
+                });
+            open
+        });
+
+        let files: Vec<DroppedFile> = ctx.input().raw.dropped_files.clone();
+        for file in files.iter() {
+            if let Err(error) = self.load_dropped_file(file) {
+                self.event_data().report(error);
             }
         }
     }
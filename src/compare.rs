@@ -0,0 +1,125 @@
+use crate::app::EncounterSource;
+use crate::encounter_details::EncounterDetails;
+use crate::i18n::tr;
+use crate::names;
+use crate::rewards::Reward;
+use crate::sprites::{show_sprite, SpriteCache, SpriteKey};
+use eframe::egui;
+use eframe::egui::{Context, RichText, Ui, Vec2};
+
+/// Encounters beyond this many no longer fit side by side.
+pub const MAX_COMPARED: usize = 4;
+
+struct Compared {
+    source: EncounterSource,
+    details: EncounterDetails,
+}
+
+impl Compared {
+    /// One value per compared row, in the order of `ROWS`.
+    fn values(&self) -> [String; 5] {
+        let d = &self.details;
+        let moves = d
+            .moves
+            .iter()
+            .map(|&m| names::move_name(m))
+            .collect::<Vec<_>>();
+        let rewards = d.rewards.iter().map(Reward::label).collect::<Vec<_>>();
+        [
+            d.tera_label().to_string(),
+            format!("{}×", d.hp_coef),
+            d.ability_label().to_string(),
+            moves.join("\n"),
+            rewards.join("\n"),
+        ]
+    }
+}
+
+const ROWS: [&str; 5] = ["Tera Type", "HP Multiplier", "Ability", "Moves", "Rewards"];
+
+/// Encounters picked for side-by-side comparison.
+#[derive(Default)]
+pub struct Comparison {
+    entries: Vec<Compared>,
+    pub open: bool,
+}
+
+impl Comparison {
+    pub fn count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.entries.len() >= MAX_COMPARED
+    }
+
+    pub fn contains(&self, source: EncounterSource) -> bool {
+        self.entries.iter().any(|entry| entry.source == source)
+    }
+
+    /// Adds an encounter unless it is already compared or the comparison is full.
+    pub fn add(&mut self, source: EncounterSource, details: EncounterDetails) {
+        if !self.contains(source) && !self.is_full() {
+            self.entries.push(Compared { source, details });
+        }
+    }
+
+    /// Draws the encounters in columns, highlighting rows where they differ. Returns true when
+    /// the user goes back.
+    pub fn show(&mut self, ui: &mut Ui, sprites: &SpriteCache, ctx: &Context) -> bool {
+        let close = ui.button(format!("⏴ {}", tr("Back"))).clicked();
+        ui.add_space(5.0);
+        if self.entries.len() < 2 {
+            ui.label(tr("Add at least two encounters to compare."));
+        }
+
+        let values = self
+            .entries
+            .iter()
+            .map(Compared::values)
+            .collect::<Vec<_>>();
+        let highlight = ui.visuals().warn_fg_color;
+        let mut remove = None;
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("comparison")
+                .striped(true)
+                .spacing(Vec2::new(20.0, 6.0))
+                .show(ui, |ui| {
+                    ui.label("");
+                    for (i, entry) in self.entries.iter().enumerate() {
+                        let d = &entry.details;
+                        let sprite = SpriteKey {
+                            shiny: d.is_always_shiny(),
+                            ..SpriteKey::new(d.species, d.form)
+                        };
+                        ui.vertical(|ui| {
+                            if let Some(image) = sprites.get(sprite, ctx) {
+                                show_sprite(ui, &image, None, Vec2::splat(64.0));
+                            }
+                            ui.strong(d.name());
+                            ui.label(format!("{}★", d.stars));
+                            if ui.small_button("✖").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                    }
+                    ui.end_row();
+
+                    for (row, &label) in ROWS.iter().enumerate() {
+                        ui.strong(tr(label));
+                        let differs = values.windows(2).any(|w| w[0][row] != w[1][row]);
+                        for value in values.iter() {
+                            let text = RichText::new(&value[row]);
+                            ui.label(if differs { text.color(highlight) } else { text });
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if let Some(i) = remove {
+            self.entries.remove(i);
+        }
+        close
+    }
+}
@@ -0,0 +1,251 @@
+use crate::app::{EncounterSource, MAX_STARS};
+
+/// State that can be shared through the URL fragment, e.g.
+/// `#stars=6&species=dragonite&event=12&version=latest&event_id=20221202&seed=1A2B3C4D`.
+/// Base game encounters are written as `base=<stars>-<index>`. Event encounters are positions in
+/// the event's table, so `event_id` records which event they refer to in case `latest` has moved
+/// on by the time the link is opened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeepLink {
+    pub star_level: u8,
+    pub all_stars: bool,
+    pub species_filter: String,
+    pub encounter: Option<EncounterSource>,
+    pub event_version: Option<String>,
+    /// `version_no` of the event's priority data.
+    pub event_id: Option<i32>,
+    pub seed: Option<u32>,
+}
+
+impl DeepLink {
+    /// Parses a URL hash or query string. Unknown keys are ignored so older links keep working.
+    pub fn parse(fragment: &str) -> Option<Self> {
+        let fragment = fragment.trim_start_matches(|c: char| c == '#' || c == '?');
+        if fragment.is_empty() {
+            return None;
+        }
+
+        let mut link = DeepLink {
+            star_level: 6,
+            all_stars: false,
+            species_filter: String::new(),
+            encounter: None,
+            event_version: None,
+            event_id: None,
+            seed: None,
+        };
+        let mut base = None;
+
+        for (key, value) in fragment.split('&').filter_map(|pair| pair.split_once('=')) {
+            let value = decode(value);
+            match key {
+                "stars" if value == "all" => link.all_stars = true,
+                "stars" => {
+                    if let Ok(stars) = value.parse::<u8>() {
+                        link.star_level = stars.clamp(1, MAX_STARS);
+                    }
+                }
+                "species" => link.species_filter = value,
+                "base" => {
+                    base = match value.split_once('-') {
+                        Some((stars, index)) => stars.parse::<u8>().ok().zip(index.parse().ok()),
+                        None => value.parse::<usize>().ok().map(|index| (0, index)),
+                    }
+                }
+                "event" => {
+                    if let Ok(index) = value.parse::<usize>() {
+                        link.encounter = Some(EncounterSource::Event { index });
+                    }
+                }
+                "version" => link.event_version = Some(value),
+                "event_id" => link.event_id = value.parse().ok(),
+                "seed" => link.seed = u32::from_str_radix(&value, 16).ok(),
+                _ => {}
+            }
+        }
+
+        if let (None, Some((stars, index))) = (link.encounter, base) {
+            link.encounter = Some(EncounterSource::Base {
+                stars: if stars == 0 { link.star_level } else { stars },
+                index,
+            });
+        }
+
+        Some(link)
+    }
+
+    pub fn to_fragment(&self) -> String {
+        let mut parts = vec![if self.all_stars {
+            "stars=all".to_string()
+        } else {
+            format!("stars={}", self.star_level)
+        }];
+        if !self.species_filter.is_empty() {
+            parts.push(format!("species={}", encode(&self.species_filter)));
+        }
+        match self.encounter {
+            Some(EncounterSource::Base { stars, index }) => {
+                parts.push(format!("base={}-{}", stars, index));
+            }
+            Some(EncounterSource::Event { index }) => {
+                parts.push(format!("event={}", index));
+                if let Some(version) = self.event_version.as_ref() {
+                    parts.push(format!("version={}", encode(version)));
+                }
+                if let Some(id) = self.event_id {
+                    parts.push(format!("event_id={}", id));
+                }
+            }
+            None => {}
+        }
+        if let Some(seed) = self.seed {
+            parts.push(format!("seed={:08X}", seed));
+        }
+        parts.join("&")
+    }
+}
+
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 3;
+                        continue;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b'+' => out.push(b' '),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link() -> DeepLink {
+        DeepLink {
+            star_level: 5,
+            all_stars: false,
+            species_filter: String::new(),
+            encounter: None,
+            event_version: None,
+            event_id: None,
+            seed: None,
+        }
+    }
+
+    fn round_trip(link: &DeepLink) -> Option<DeepLink> {
+        DeepLink::parse(&format!("#{}", link.to_fragment()))
+    }
+
+    fn species(fragment: &str) -> String {
+        DeepLink::parse(fragment).unwrap().species_filter
+    }
+
+    #[test]
+    fn round_trips_base_encounters() {
+        let link = DeepLink {
+            species_filter: "dragonite".to_string(),
+            encounter: Some(EncounterSource::Base {
+                stars: 5,
+                index: 12,
+            }),
+            ..link()
+        };
+        assert_eq!(link.to_fragment(), "stars=5&species=dragonite&base=5-12");
+        assert_eq!(round_trip(&link), Some(link));
+    }
+
+    #[test]
+    fn round_trips_event_encounters() {
+        let link = DeepLink {
+            star_level: 7,
+            species_filter: "tera:dragon stars:7".to_string(),
+            encounter: Some(EncounterSource::Event { index: 3 }),
+            event_version: Some("latest".to_string()),
+            event_id: Some(20221202),
+            ..link()
+        };
+        assert_eq!(
+            link.to_fragment(),
+            "stars=7&species=tera%3Adragon%20stars%3A7&event=3&version=latest&event_id=20221202"
+        );
+        assert_eq!(round_trip(&link), Some(link));
+    }
+
+    #[test]
+    fn round_trips_seeds_and_all_stars() {
+        let link = DeepLink {
+            star_level: 6,
+            all_stars: true,
+            seed: Some(0x0A2B3C4D),
+            ..link()
+        };
+        assert_eq!(link.to_fragment(), "stars=all&seed=0A2B3C4D");
+        assert_eq!(round_trip(&link), Some(link));
+    }
+
+    #[test]
+    fn reads_legacy_base_indices() {
+        let link = DeepLink::parse("#stars=4&base=9").unwrap();
+        assert_eq!(
+            link.encounter,
+            Some(EncounterSource::Base { stars: 4, index: 9 })
+        );
+        // The star level applies even when it comes after the index.
+        let link = DeepLink::parse("base=9&stars=3").unwrap();
+        assert_eq!(
+            link.encounter,
+            Some(EncounterSource::Base { stars: 3, index: 9 })
+        );
+    }
+
+    #[test]
+    fn decodes_species_filters() {
+        assert_eq!(species("species=mr.%20mime"), "mr. mime");
+        assert_eq!(species("species=mr.+mime"), "mr. mime");
+        assert_eq!(species("species=flab%C3%A9b%C3%A9"), "flabébé");
+        assert_eq!(decode(&encode("ポケモン 100%")), "ポケモン 100%");
+    }
+
+    #[test]
+    fn keeps_malformed_escapes() {
+        assert_eq!(species("species=100%"), "100%");
+        assert_eq!(species("species=%4"), "%4");
+        assert_eq!(species("species=%zz1"), "%zz1");
+        assert_eq!(species("species=%E9t%E9"), "\u{FFFD}t\u{FFFD}");
+    }
+
+    #[test]
+    fn ignores_empty_and_unknown_input() {
+        assert_eq!(DeepLink::parse(""), None);
+        assert_eq!(DeepLink::parse("#"), None);
+        let link = DeepLink::parse("#stars=9&seed=xyz&colour=red").unwrap();
+        assert_eq!(link.star_level, MAX_STARS);
+        assert_eq!(link.seed, None);
+    }
+}
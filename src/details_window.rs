@@ -1,141 +1,342 @@
-use eframe::egui::Context;
-#[allow(unused_imports)]
-use egui_extras::RetainedImage;
-use std::sync::{Arc, Mutex};
-#[allow(unused_imports)]
+use crate::encounter_details::EncounterDetails;
+use crate::i18n::tr;
+use crate::matchup::{move_type_label, Matchup};
+use crate::names;
+use crate::progress::{crystal_kind, StoryProgress};
+use crate::rewards::Reward;
+use crate::seed::{generate, parse_seed, Shininess};
+use crate::sprites::{show_sprite, SpriteCache, SpriteKey};
+use crate::stats::{StatCalculator, STAT_LABELS};
+use crate::version::SpawnChance;
+use eframe::egui;
+use eframe::egui::{Context, Ui};
 use sv_raid_reader::{
-    personal_table, ExtraActionTrigger, ExtraActionType, GemType, Gender, ItemSubject, ItemTable,
-    IvType, PersonalInfo, RaidEncounter, Seikaku, ShinyType, Tokusei, ABILITIES, ITEMS,
-    LOTTERY_ITEMS, NATURES, SPECIES, TYPES,
+    personal_table, ExtraAction, ExtraActionTrigger, ExtraActionType, PersonalInfo, RaidEncounter,
+    ShinyType, Tokusei,
 };
 
+/// Parts of the details view, each shown on its own tab.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DetailsTab {
+    Overview,
+    Stats,
+    Moves,
+    Rewards,
+    Probability,
+    Seed,
+}
+
+impl DetailsTab {
+    pub const ALL: [DetailsTab; 6] = [
+        DetailsTab::Overview,
+        DetailsTab::Stats,
+        DetailsTab::Moves,
+        DetailsTab::Rewards,
+        DetailsTab::Probability,
+        DetailsTab::Seed,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DetailsTab::Overview => "Overview",
+            DetailsTab::Stats => "Stats",
+            DetailsTab::Moves => "Moves & Actions",
+            DetailsTab::Rewards => "Rewards",
+            DetailsTab::Probability => "Probability",
+            DetailsTab::Seed => "Seed Tools",
+        }
+    }
+}
+
+/// Delivery group of an event encounter.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EventGroup {
+    pub id: u8,
+    /// Share of event crystals in percent, `None` without priority data. Groups the priority
+    /// data disables have a chance of 0.
+    pub chance: Option<f32>,
+}
+
 #[derive(Clone)]
 pub struct DetailsWindow {
-    pub species: String,
-    pub level: String,
-    pub stars: String,
-    pub ability: String,
-    pub nature: String,
-    pub gender: String,
-    pub hp: String,
-    pub base_stats: String,
-    pub base_type: String,
-    pub image: Arc<Mutex<Option<RetainedImage>>>,
+    pub details: EncounterDetails,
+    pub spawn_chances: Vec<SpawnChance>,
+    pub event_group: Option<EventGroup>,
+    pub stat_calculator: StatCalculator,
+    pub defense_types: Vec<usize>,
+    pub matchup: Matchup,
+    pub sprite: SpriteKey,
+    pub show_tera_crystal: bool,
 }
 
 impl DetailsWindow {
-    pub fn new(encounter: &RaidEncounter, ctx: &Context) -> Self {
-        let _gem_type = match encounter.gem_type {
-            GemType::Normal => "Normal",
-            GemType::Fighting => "Fighting",
-            GemType::Flying => "Flying",
-            GemType::Poison => "Poison",
-            GemType::Ground => "Ground",
-            GemType::Rock => "Rock",
-            GemType::Bug => "Bug",
-            GemType::Ghost => "Ghost",
-            GemType::Steel => "Steel",
-            GemType::Fire => "Fire",
-            GemType::Water => "Water",
-            GemType::Grass => "Grass",
-            GemType::Electric => "Electric",
-            GemType::Psychic => "Psychic",
-            GemType::Ice => "Ice",
-            GemType::Dragon => "Dragon",
-            GemType::Dark => "Dark",
-            GemType::Fairy => "Fairy",
-            GemType::Random => "Random",
-        };
+    pub fn new(
+        encounter: &RaidEncounter,
+        spawn_chances: &[SpawnChance],
+        rewards: Vec<Reward>,
+    ) -> Self {
+        let details = EncounterDetails::new(encounter, rewards);
+        Self {
+            spawn_chances: spawn_chances.to_vec(),
+            event_group: None,
+            stat_calculator: StatCalculator::new(&details),
+            defense_types: details.defense_types(),
+            matchup: Matchup::new(&details),
+            sprite: SpriteKey {
+                shiny: details.is_always_shiny(),
+                ..SpriteKey::new(details.species, details.form)
+            },
+            show_tera_crystal: true,
+            details,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        format!("{} {}★", self.details.name(), self.details.stars)
+    }
 
-        let ability = match encounter.tokusei {
-            Tokusei::Random12 => "Random 1/2",
-            Tokusei::Random123 => "Random 1/2/H",
-            Tokusei::Set1 => {
-                ABILITIES[personal_table::SV
-                    .get_form_entry(encounter.species as usize, encounter.form as usize)
-                    .get_ability_index(0)
-                    .unwrap()]
+    /// Sprite with the shiny and Tera crystal toggles under it.
+    pub fn sprite_ui(&mut self, ui: &mut Ui, sprites: &SpriteCache, ctx: &Context) {
+        ui.vertical(|ui| {
+            if let Some(image) = sprites.get(self.sprite, ctx) {
+                let crystal = self.details.tera_type.filter(|_| self.show_tera_crystal);
+                show_sprite(ui, &image, crystal, image.size_vec2());
             }
-            Tokusei::Set2 => {
-                ABILITIES[personal_table::SV
-                    .get_form_entry(encounter.species as usize, encounter.form as usize)
-                    .get_ability_index(1)
-                    .unwrap()]
+            // Always-shiny raids already show the shiny sprite and shiny-locked ones can't be
+            // shiny, so the toggle is only for raids that may or may not be.
+            let can_toggle = !matches!(self.details.shiny, ShinyType::Always | ShinyType::Never)
+                && sprites.has_shiny(self.sprite, ctx);
+            ui.add_enabled(
+                can_toggle,
+                egui::Checkbox::new(&mut self.sprite.shiny, tr("Shiny")),
+            );
+            if self.details.tera_type.is_some() {
+                ui.checkbox(&mut self.show_tera_crystal, tr("Tera Crystal"));
             }
-            Tokusei::Set3 => {
-                ABILITIES[personal_table::SV
-                    .get_form_entry(encounter.species as usize, encounter.form as usize)
-                    .get_ability_index(2)
-                    .unwrap()]
+        });
+    }
+
+    /// Type, base stats and the raid's fixed build.
+    pub fn overview_ui(&self, ui: &mut Ui) {
+        let details = &self.details;
+        ui.label(format!("{} {}", tr("Base Type:"), details.type_label()));
+        ui.label(format!(
+            "{} {}",
+            tr("Base Stats:"),
+            details.base_stats_label()
+        ));
+        egui::Grid::new("stars_levels").show(ui, |ui| {
+            ui.label(format!("{} {}", tr("Raid Level:"), details.level));
+            ui.label(format!("{} {}", tr("HP:"), details.hp_coef));
+            ui.end_row();
+            ui.label(format!("{} {}", tr("Stars:"), details.stars));
+            ui.label(format!("{} {}", tr("Gender:"), details.gender_label()));
+            ui.end_row();
+            ui.label(format!("{} {}", tr("Nature:"), details.nature_label()));
+            ui.label(format!("{} {}", tr("Ability:"), details.ability_label()));
+        });
+    }
+
+    pub fn base_stats_ui(&self, ui: &mut Ui) {
+        egui::Grid::new("base_stats").striped(true).show(ui, |ui| {
+            for (label, stat) in STAT_LABELS.iter().zip(self.details.base_stats) {
+                ui.label(*label);
+                ui.label(stat.to_string());
+                ui.end_row();
             }
-        };
+            ui.strong(tr("Total"));
+            ui.strong(self.details.base_stats.iter().sum::<u16>().to_string());
+            ui.end_row();
+        });
+    }
 
-        let nature = match encounter.seikaku {
-            Seikaku::Random => "Random",
-            i => NATURES[i as usize - 1],
-        };
+    pub fn moves_ui(&self, ui: &mut Ui) {
+        egui::Grid::new("moves").show(ui, |ui| {
+            for &m in self.details.moves.iter() {
+                ui.label(names::move_name(m));
+                ui.label(move_type_label(m, self.details.tera_type));
+                ui.end_row();
+            }
+        });
+    }
 
-        let gender = match encounter.gender {
-            Gender::Random => "Random",
-            Gender::Male => "Male",
-            Gender::Female => "Female",
-        };
+    pub fn rewards_ui(&self, ui: &mut Ui) {
+        if self.details.rewards.is_empty() {
+            ui.label(tr("None"));
+        }
+        for reward in self.details.rewards.iter() {
+            ui.label(reward.label());
+        }
+    }
 
-        let mut base_stats = personal_table::SV
-            .get_form_entry(encounter.species as usize, encounter.form as usize)
-            .stats();
+    /// Event group and the chance of seeing the encounter in each version.
+    pub fn probability_ui(&self, ui: &mut Ui, progress: StoryProgress) {
+        if !progress.unlocks(self.details.stars) {
+            ui.label(format!(
+                "{}★ {} ({})",
+                self.details.stars,
+                tr("raids are not available yet"),
+                progress.name()
+            ));
+        }
+        match self.event_group {
+            Some(group) => {
+                let name = format!("{} {}", tr("Event Group"), group.id);
+                ui.label(match group.chance {
+                    Some(chance) if chance == 0.0 => {
+                        format!("{} ({})", name, tr("inactive in the loaded priority data"))
+                    }
+                    Some(chance) => {
+                        format!("{} ({:.1}% {})", name, chance, tr("of event crystals"))
+                    }
+                    None => name,
+                });
+                for chance in self.spawn_chances.iter() {
+                    ui.label(format!(
+                        "{}, {:.2}% ({}★ {})",
+                        chance.label(),
+                        chance.chance,
+                        self.details.stars,
+                        tr("event crystals")
+                    ));
+                }
+            }
+            None => {
+                for chance in self.spawn_chances.iter() {
+                    ui.label(format!(
+                        "{} ({:.2}%), {}: {:.2}% ({})",
+                        chance.label(),
+                        chance.chance,
+                        crystal_kind(self.details.stars),
+                        progress.appearance_chance(self.details.stars, chance),
+                        progress.name()
+                    ));
+                }
+            }
+        }
+    }
 
-        base_stats.swap(3, 4);
-        base_stats.swap(4, 5);
+    /// What the raid's seed rolls for this encounter.
+    pub fn seed_ui(&self, ui: &mut Ui, seed: &str) {
+        if seed.trim().is_empty() {
+            ui.label(tr("Enter a raid seed to see the Pokémon it generates."));
+            return;
+        }
+        let seed = match parse_seed(seed) {
+            Some(seed) => seed,
+            None => {
+                ui.colored_label(ui.visuals().error_fg_color, tr("Invalid seed"));
+                return;
+            }
+        };
 
-        let stats_str = base_stats
-            .into_iter()
-            .map(|i| format!("{:0>2}", i))
+        let result = generate(&self.details, seed);
+        let ivs = result
+            .ivs
+            .iter()
+            .map(|iv| iv.to_string())
             .collect::<Vec<_>>()
-            .join(" - ");
-
-        let type_1 = personal_table::SV
-            .get_form_entry(encounter.species as usize, encounter.form as usize)
-            .get_type_1();
-        let type_2 = personal_table::SV
-            .get_form_entry(encounter.species as usize, encounter.form as usize)
-            .get_type_2();
-
-        let base_type = if type_1 != type_2 && type_2 < TYPES.len() {
-            format!("Base Type: {}/{}", TYPES[type_1], TYPES[type_2])
-        } else {
-            format!("Base Type: {}", TYPES[type_1])
+            .join("/");
+        let shiny = match result.shiny {
+            Shininess::None => tr("No"),
+            Shininess::Star => "★",
+            Shininess::Square => "■",
         };
+        let ability = match result.ability {
+            Some(ability) => names::ability(ability).to_string(),
+            None => format!("{} {}", tr("Slot"), result.ability_slot + 1),
+        };
+        egui::Grid::new("seed_result").striped(true).show(ui, |ui| {
+            ui.label("EC");
+            ui.label(format!("{:08X}", result.encryption_constant));
+            ui.end_row();
+            ui.label("PID");
+            ui.label(format!("{:08X}", result.pid));
+            ui.end_row();
+            ui.label(tr("Shiny"));
+            ui.label(shiny);
+            ui.end_row();
+            ui.label(tr("IVs"));
+            ui.label(ivs);
+            ui.end_row();
+            ui.label(tr("Ability"));
+            ui.label(ability);
+            ui.end_row();
+        });
+    }
 
-        let image_url = format!("https://raw.githubusercontent.com/Lincoln-LM/sv-live-map/master/resources/sprites/{}{}.png", encounter.species, if encounter.form != 0 { format!("-{}", encounter.form) } else { "".to_string() });
+    pub fn extra_actions_ui(&self, ui: &mut Ui) {
+        let actions = self
+            .details
+            .extra_actions
+            .iter()
+            .filter_map(extra_action_label)
+            .collect::<Vec<_>>();
+        if actions.is_empty() {
+            ui.label(tr("None"));
+        }
+        for action in actions {
+            ui.label(action);
+        }
+    }
+}
 
-        let image = Arc::new(Mutex::new(None));
+pub fn extra_action_label(action: &ExtraAction) -> Option<String> {
+    let what = match action.action {
+        ExtraActionType::None => return None,
+        ExtraActionType::BossStatusReset => tr("Resets its stat changes").to_string(),
+        ExtraActionType::PlayerStatusReset => tr("Resets your stat changes").to_string(),
+        ExtraActionType::Move => {
+            format!("{} {}", tr("Uses move:"), names::move_name(action.move_id))
+        }
+        ExtraActionType::GemCount => tr("Drains your Tera Orb charge").to_string(),
+    };
+    let when = match action.trigger {
+        ExtraActionTrigger::None => return None,
+        ExtraActionTrigger::Time => tr("Time left:"),
+        ExtraActionTrigger::Hp => tr("HP left:"),
+    };
+    Some(format!("{} {}% - {}", when, action.value, what))
+}
 
-        let image_request = ehttp::Request::get(image_url);
+/// Extra information for 7★ Mightiest Mark raids, empty for every other star level.
+pub fn mightiest_mark_notes(details: &EncounterDetails) -> Vec<String> {
+    if details.stars != 7 {
+        return vec![];
+    }
 
-        let clone = image.clone();
-        let ctx = ctx.clone();
-        ehttp::fetch(image_request, move |response| {
-            if let Ok(response) = response {
-                if let Ok(image) = RetainedImage::from_image_bytes(&response.url, &response.bytes) {
-                    let mut lock = clone.lock().unwrap();
-                    *lock = Some(image);
-                    ctx.request_repaint();
-                }
-            }
-        });
+    vec![
+        format!(
+            "{} {} {}, {} {}, {} {}, {} {}",
+            tr("Fixed build:"),
+            tr("Tera Type:"),
+            details.tera_label(),
+            tr("Nature:"),
+            details.nature_label(),
+            tr("Ability:"),
+            details.ability_label(),
+            tr("IVs:"),
+            details.ivs_label()
+        ),
+        tr("Caught Pokémon carries the Mightiest Mark").to_string(),
+        tr("Can only be caught once per save file, later wins only give rewards").to_string(),
+        tr(
+            "Shield phases follow the extra action timeline in Moves & Actions, Terastallized \
+            attacks break the shield fastest",
+        )
+        .to_string(),
+    ]
+}
 
-        Self {
-            species: format!("Species: {}", SPECIES[encounter.species as usize]),
-            level: format!("Raid Level: {}", encounter.level),
-            stars: format!("Stars: {}", encounter.difficulty),
-            ability: format!("Ability: {}", ability),
-            nature: format!("Nature: {}", nature),
-            gender: format!("Gender: {}", gender),
-            hp: format!("HP: {}", encounter.hp_coef),
-            base_stats: format!("Base Stats: {}", stats_str),
-            base_type,
-            image,
-        }
-    }
+/// Index into `ABILITIES` for encounters that lock the ability slot.
+pub fn fixed_ability_index(encounter: &RaidEncounter) -> Option<usize> {
+    let slot = match encounter.tokusei {
+        Tokusei::Set1 => 0,
+        Tokusei::Set2 => 1,
+        Tokusei::Set3 => 2,
+        Tokusei::Random12 | Tokusei::Random123 => return None,
+    };
+    personal_table::SV
+        .get_form_entry(encounter.species as usize, encounter.form as usize)
+        .get_ability_index(slot)
 }
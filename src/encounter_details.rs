@@ -0,0 +1,189 @@
+use crate::forms::display_name;
+use crate::i18n::tr;
+use crate::names;
+use crate::rewards::Reward;
+use crate::stats::base_stats;
+use crate::types::{base_types, gem_type_index};
+use sv_raid_reader::{
+    personal_table, ExtraAction, Gender, IvType, PersonalInfo, RaidEncounter, Seikaku, ShinyType,
+    Tokusei,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AbilitySpec {
+    /// Ability slot 0-2 and the ability in it, if the species has one there.
+    Fixed {
+        slot: usize,
+        ability: Option<usize>,
+    },
+    Random12,
+    Random123,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IvSpec {
+    /// HP, Atk, Def, SpA, SpD, Spe.
+    Fixed([u8; 6]),
+    /// Number of IVs guaranteed to be 31, the rest are random.
+    Perfect(u8),
+    Random,
+}
+
+/// Everything about an encounter the app shows, resolved from a `RaidEncounter` once. Names
+/// are looked up when formatting so they follow the selected language.
+#[derive(Clone)]
+pub struct EncounterDetails {
+    pub species: u16,
+    pub form: u8,
+    pub stars: u8,
+    pub level: u8,
+    pub hp_coef: u16,
+    /// Percentage of its HP at which the boss raises its shield, 0 if it never does.
+    pub shield_trigger_hp: u8,
+    /// Base types as indices into `TYPES`.
+    pub types: Vec<usize>,
+    pub tera_type: Option<usize>,
+    pub ability: AbilitySpec,
+    pub nature: Option<usize>,
+    pub gender: Gender,
+    pub shiny: ShinyType,
+    pub ivs: IvSpec,
+    /// HP, Atk, Def, SpA, SpD, Spe.
+    pub base_stats: [u16; 6],
+    pub moves: Vec<u16>,
+    pub extra_actions: Vec<ExtraAction>,
+    pub rewards: Vec<Reward>,
+}
+
+impl EncounterDetails {
+    pub fn new(encounter: &RaidEncounter, rewards: Vec<Reward>) -> Self {
+        let personal =
+            personal_table::SV.get_form_entry(encounter.species as usize, encounter.form as usize);
+        let fixed = |slot| AbilitySpec::Fixed {
+            slot,
+            ability: personal.get_ability_index(slot),
+        };
+        let ability = match encounter.tokusei {
+            Tokusei::Random12 => AbilitySpec::Random12,
+            Tokusei::Random123 => AbilitySpec::Random123,
+            Tokusei::Set1 => fixed(0),
+            Tokusei::Set2 => fixed(1),
+            Tokusei::Set3 => fixed(2),
+        };
+        let ivs = match encounter.iv_type {
+            IvType::Value => IvSpec::Fixed(encounter.ivs),
+            IvType::VNum => IvSpec::Perfect(encounter.flawless_ivs),
+            IvType::Random => IvSpec::Random,
+        };
+
+        Self {
+            species: encounter.species,
+            form: encounter.form,
+            stars: encounter.difficulty,
+            level: encounter.level,
+            hp_coef: encounter.hp_coef,
+            shield_trigger_hp: encounter.power_charge_trigger_hp,
+            types: base_types(encounter.species, encounter.form),
+            tera_type: gem_type_index(encounter.gem_type),
+            ability,
+            nature: match encounter.seikaku {
+                Seikaku::Random => None,
+                nature => Some(nature as usize - 1),
+            },
+            gender: encounter.gender,
+            shiny: encounter.shiny,
+            ivs,
+            base_stats: base_stats(encounter.species, encounter.form),
+            moves: encounter
+                .moves
+                .iter()
+                .copied()
+                .filter(|&m| m != 0)
+                .collect(),
+            extra_actions: encounter.extra_actions.to_vec(),
+            rewards,
+        }
+    }
+
+    /// Species in the selected language with its form, e.g. "Tauros (Paldean Aqua Breed)".
+    pub fn name(&self) -> String {
+        display_name(self.species, self.form)
+    }
+
+    /// Types the boss defends with. Bosses are always Terastallized, so a set Tera type
+    /// replaces the base types entirely.
+    pub fn defense_types(&self) -> Vec<usize> {
+        match self.tera_type {
+            Some(tera) => vec![tera],
+            None => self.types.clone(),
+        }
+    }
+
+    /// Index into `ABILITIES` for encounters that lock the ability slot.
+    pub fn fixed_ability(&self) -> Option<usize> {
+        match self.ability {
+            AbilitySpec::Fixed { ability, .. } => ability,
+            AbilitySpec::Random12 | AbilitySpec::Random123 => None,
+        }
+    }
+
+    pub fn type_label(&self) -> String {
+        self.types
+            .iter()
+            .map(|&t| names::type_name(t))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    pub fn tera_label(&self) -> &'static str {
+        self.tera_type.map_or(tr("Random"), names::type_name)
+    }
+
+    pub fn ability_label(&self) -> &'static str {
+        match self.ability {
+            AbilitySpec::Fixed {
+                ability: Some(ability),
+                ..
+            } => names::ability(ability),
+            AbilitySpec::Fixed { .. } => tr("Unknown"),
+            AbilitySpec::Random12 => tr("Random 1/2"),
+            AbilitySpec::Random123 => tr("Random 1/2/H"),
+        }
+    }
+
+    pub fn nature_label(&self) -> &'static str {
+        self.nature.map_or(tr("Random"), names::nature)
+    }
+
+    pub fn gender_label(&self) -> &'static str {
+        match self.gender {
+            Gender::Random => tr("Random"),
+            Gender::Male => tr("Male"),
+            Gender::Female => tr("Female"),
+        }
+    }
+
+    pub fn ivs_label(&self) -> String {
+        match self.ivs {
+            IvSpec::Fixed(ivs) => ivs
+                .iter()
+                .map(|iv| iv.to_string())
+                .collect::<Vec<_>>()
+                .join("/"),
+            IvSpec::Perfect(count) => format!("{} {}", count, tr("guaranteed perfect")),
+            IvSpec::Random => tr("Random").to_string(),
+        }
+    }
+
+    pub fn base_stats_label(&self) -> String {
+        self.base_stats
+            .iter()
+            .map(|i| format!("{:0>2}", i))
+            .collect::<Vec<_>>()
+            .join(" - ")
+    }
+
+    pub fn is_always_shiny(&self) -> bool {
+        self.shiny == ShinyType::Always
+    }
+}
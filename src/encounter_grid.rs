@@ -1,50 +1,161 @@
-use crate::app::SVRaidLookup;
-use crate::details_window::DetailsWindow;
+use crate::app::{EncounterSource, SVRaidLookup};
+use crate::encounter_index::{refresh_index, IndexEntry, Row, SectionHeader};
+use crate::forms::display_name;
+use crate::i18n::tr;
+use crate::names;
+use crate::progress::crystal_kind;
+use crate::sprites::{show_sprite, SpriteCache, SpriteKey};
+use crate::types::{gem_type_index, TYPE_COLORS};
 use eframe::egui;
-use eframe::egui::{Context, Ui, Vec2};
-use sv_raid_reader::SPECIES;
-
-pub fn encounter_grid(app: &mut SVRaidLookup, ui: &mut Ui, ctx: &Context) {
-    egui::Grid::new("encounters")
-        .spacing(Vec2::new(5.0, 2.0))
-        .min_col_width(100.0)
-        .show(ui, |ui| {
-            for (i, encounter) in app
-                .encounters
-                .iter()
-                .filter(|e| {
-                    SPECIES[e.species as usize]
-                        .to_lowercase()
-                        .contains(&app.species_filter.to_lowercase())
-                })
-                .enumerate()
-            {
-                ui.vertical_centered_justified(|ui| {
-                    if ui.button(SPECIES[encounter.species as usize]).clicked() {
-                        if let Some(details) = app.details_window.as_mut() {
-                            *details = DetailsWindow::new(encounter, ctx);
-                        } else {
-                            app.details_window = Some(DetailsWindow::new(encounter, ctx));
+use eframe::egui::{
+    pos2, vec2, Align, Color32, Context, Layout, Rect, Response, RichText, Sense, Ui, Vec2,
+};
+use sv_raid_reader::GemType;
+
+fn row_height(row: &Row) -> f32 {
+    match row {
+        Row::Header(_) => HEADER_HEIGHT,
+        Row::Cards { .. } => CARD_SIZE.y + CARD_SPACING,
+    }
+}
+
+/// Scrollable encounter list, at most `max_height` tall. Matching encounters come from the
+/// cached index, and only the rows inside the visible part of the scroll area are laid out.
+pub fn encounter_grid(app: &mut SVRaidLookup, ui: &mut Ui, ctx: &Context, max_height: f32) {
+    refresh_index(app);
+
+    let mut clicked = None;
+    egui::ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .max_height(max_height)
+        .show_viewport(ui, |ui, viewport| {
+            let width = ui.available_width();
+            let columns = ((width + CARD_SPACING) / (CARD_SIZE.x + CARD_SPACING))
+                .floor()
+                .max(1.0) as usize;
+
+            app.encounter_index.layout_rows(columns);
+            let sections = &app.encounter_index.sections;
+            let rows = &app.encounter_index.rows;
+            ui.set_height(rows.iter().map(row_height).sum());
+
+            let origin = ui.max_rect().min;
+            let mut y = 0.0;
+            for row in rows.iter() {
+                let height = row_height(row);
+                if y + height >= viewport.min.y && y <= viewport.max.y {
+                    let rect = Rect::from_min_size(origin + vec2(0.0, y), vec2(width, height));
+                    match *row {
+                        Row::Header(header) => section_header(ui, rect, header),
+                        Row::Cards { section, start } => {
+                            let entries = &sections[section].entries;
+                            let end = (start + columns).min(entries.len());
+                            for (column, entry) in entries[start..end].iter().enumerate() {
+                                let card = Rect::from_min_size(
+                                    pos2(
+                                        rect.left() + column as f32 * (CARD_SIZE.x + CARD_SPACING),
+                                        rect.top(),
+                                    ),
+                                    CARD_SIZE,
+                                );
+                                let id = ui.id().with(("card", section, start + column));
+                                if encounter_card(ui, ctx, &app.sprites, card, id, entry).clicked()
+                                {
+                                    clicked = Some((entry.source, entry.encounter));
+                                }
+                            }
                         }
                     }
-                });
-                if (i + 1) % 2 == 0 {
-                    ui.end_row();
                 }
+                y += height;
             }
-            ui.end_row();
-            for (_i, _encounter) in app
-                .event_encounters
-                .lock()
-                .unwrap()
-                .iter()
-                .filter(|e| {
-                    e.species != 0
-                        && SPECIES[e.species as usize]
-                            .to_lowercase()
-                            .contains(&app.species_filter.to_lowercase())
-                })
-                .enumerate()
-            {}
         });
+
+    if let Some((source, encounter)) = clicked {
+        app.select(source, &encounter, ctx);
+    }
+}
+
+fn section_header(ui: &mut Ui, rect: Rect, header: SectionHeader) {
+    let mut ui = ui.child_ui(rect, Layout::left_to_right(Align::BOTTOM));
+    match header {
+        SectionHeader::Stars { stars, chance } => {
+            let text = match stars {
+                6 | 7 if chance > 0.0 => format!("{}★ ({})", stars, crystal_kind(stars)),
+                _ => format!("{}★ ({:.0}%)", stars, chance),
+            };
+            ui.label(RichText::new(text).strong());
+        }
+        SectionHeader::EventGroup { group, chance } => {
+            ui.label(RichText::new(format!("{} {}", tr("Event Group"), group)).strong());
+            match chance {
+                Some(chance) if chance > 0.0 => {
+                    ui.label(format!("{:.1}%", chance));
+                }
+                Some(_) => {
+                    ui.colored_label(Color32::RED, tr("Inactive"));
+                }
+                None => {}
+            }
+        }
+    }
+}
+const HEADER_HEIGHT: f32 = 24.0;
+const CARD_SIZE: Vec2 = Vec2::new(110.0, 118.0);
+const SPRITE_SIZE: f32 = 48.0;
+const CARD_SPACING: f32 = 5.0;
+
+fn tera_badge(gem_type: GemType) -> RichText {
+    let (name, color) = match gem_type_index(gem_type) {
+        Some(t) => (names::type_name(t), TYPE_COLORS[t]),
+        None => (names::gem_type(gem_type), Color32::GRAY),
+    };
+    RichText::new(format!(" {} ", name))
+        .small()
+        .color(Color32::BLACK)
+        .background_color(color)
+}
+
+/// Draws one card into `rect`. The sprite is only requested once the card is on screen.
+fn encounter_card(
+    ui: &mut Ui,
+    ctx: &Context,
+    sprites: &SpriteCache,
+    rect: Rect,
+    id: egui::Id,
+    entry: &IndexEntry,
+) -> Response {
+    let response = ui.interact(rect, id, Sense::click());
+    if !ui.is_rect_visible(rect) {
+        return response;
+    }
+
+    let encounter = &entry.encounter;
+    let visuals = ui.style().interact(&response);
+    ui.painter()
+        .rect(rect, visuals.rounding, visuals.bg_fill, visuals.bg_stroke);
+
+    let mut card = ui.child_ui(rect.shrink(4.0), Layout::top_down(Align::Center));
+    match sprites.get(SpriteKey::for_encounter(encounter), ctx) {
+        Some(image) => {
+            show_sprite(
+                &mut card,
+                &image,
+                gem_type_index(encounter.gem_type),
+                Vec2::splat(SPRITE_SIZE),
+            );
+        }
+        None => {
+            card.add_space(SPRITE_SIZE);
+        }
+    }
+    card.label(RichText::new(display_name(encounter.species, encounter.form)).small());
+    card.horizontal(|ui| {
+        ui.label(RichText::new(format!("{}★ {:.1}%", encounter.difficulty, entry.chance)).small());
+        if matches!(entry.source, EncounterSource::Event { .. }) {
+            ui.label(RichText::new(tr("Event")).small().color(Color32::GOLD));
+        }
+    });
+    card.label(tera_badge(encounter.gem_type));
+    response
 }
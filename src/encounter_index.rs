@@ -0,0 +1,245 @@
+use crate::app::{base_encounters, difficulty_table, EncounterSource, SVRaidLookup, MAX_STARS};
+use crate::filters::EncounterFilter;
+use crate::forms::search_text;
+use crate::names::{self, language, Language};
+use crate::priority::event_chances;
+use crate::progress::{grid_chance, StoryProgress};
+use crate::query::Query;
+use crate::version::{
+    available_in, chances_from_totals, version_chance, version_totals, GameVersion,
+};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use sv_raid_reader::{RaidEncounter, SPECIES};
+
+/// Everything the encounter list depends on. The index is rebuilt only when this changes.
+#[derive(Clone, PartialEq)]
+struct IndexKey {
+    data_version: usize,
+    star_level: u8,
+    all_stars: bool,
+    query: Query,
+    filter: EncounterFilter,
+    game_version: Option<GameVersion>,
+    progress: StoryProgress,
+    language: Language,
+}
+
+impl IndexKey {
+    fn new(app: &SVRaidLookup) -> Self {
+        Self {
+            data_version: app.data_version.load(Ordering::Relaxed),
+            star_level: app.star_level,
+            all_stars: app.all_stars,
+            query: app.query.clone(),
+            filter: app.filter.clone(),
+            game_version: app.game_version,
+            progress: app.progress,
+            language: language(),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct IndexEntry {
+    pub source: EncounterSource,
+    pub encounter: RaidEncounter,
+    pub chance: f32,
+}
+
+#[derive(Copy, Clone)]
+pub enum SectionHeader {
+    Stars {
+        stars: u8,
+        chance: f32,
+    },
+    /// `chance` is `None` without priority data and 0 for groups the priority data disables.
+    EventGroup {
+        group: u8,
+        chance: Option<f32>,
+    },
+}
+
+pub struct Section {
+    pub header: Option<SectionHeader>,
+    pub entries: Vec<IndexEntry>,
+}
+
+/// One line of the grid: a section heading or a row of cards from a section.
+pub enum Row {
+    Header(SectionHeader),
+    Cards { section: usize, start: usize },
+}
+
+/// The filtered encounter list, grouped into sections the grid draws as-is.
+#[derive(Default)]
+pub struct EncounterIndex {
+    key: Option<IndexKey>,
+    /// Lowercase English, translated and form names, built once per language.
+    search_text: HashMap<(u16, u8), String>,
+    search_language: Option<Language>,
+    pub sections: Vec<Section>,
+    pub rows: Vec<Row>,
+    /// Cards per row `rows` were laid out for, `None` after the sections change.
+    row_columns: Option<usize>,
+}
+
+impl EncounterIndex {
+    fn search_text(&mut self, encounter: &RaidEncounter) -> &str {
+        self.search_text
+            .entry((encounter.species, encounter.form))
+            .or_insert_with(|| search_text(encounter.species, encounter.form))
+    }
+
+    /// Splits the sections into rows of `columns` cards, keeping the previous rows while
+    /// neither the sections nor the column count changed.
+    pub fn layout_rows(&mut self, columns: usize) {
+        if self.row_columns == Some(columns) {
+            return;
+        }
+        self.rows.clear();
+        for (section, s) in self.sections.iter().enumerate() {
+            if let Some(header) = s.header {
+                self.rows.push(Row::Header(header));
+            }
+            for start in (0..s.entries.len()).step_by(columns) {
+                self.rows.push(Row::Cards { section, start });
+            }
+        }
+        self.row_columns = Some(columns);
+    }
+}
+
+/// Rebuilds the index if anything it depends on changed since the last frame.
+pub fn refresh_index(app: &mut SVRaidLookup) {
+    let key = IndexKey::new(app);
+    if app.encounter_index.key.as_ref() == Some(&key) {
+        return;
+    }
+
+    let mut index = std::mem::take(&mut app.encounter_index);
+    if index.search_language != Some(key.language) {
+        index.search_text.clear();
+        index.search_language = Some(key.language);
+    }
+    index.sections = build_sections(app, &mut index);
+    index.row_columns = None;
+    index.key = Some(key);
+    app.encounter_index = index;
+}
+
+fn build_sections(app: &SVRaidLookup, index: &mut EncounterIndex) -> Vec<Section> {
+    let mut matches_filter = |source: EncounterSource, e: &RaidEncounter| {
+        app.game_version.map_or(true, |v| available_in(e, v))
+            && app
+                .query
+                .matches(e, index.search_text(e), || app.rewards(source, e))
+            && app.filter.matches(e, || app.rewards(source, e))
+    };
+
+    // A `stars:` term in the query searches that level even when it is not the selected one.
+    let star_levels = match app.query.stars() {
+        Some(stars) => stars..=stars,
+        None if app.all_stars => 1..=MAX_STARS,
+        None => app.star_level..=app.star_level,
+    };
+
+    let events = app
+        .event_encounters
+        .lock()
+        .map(|events| events.clone())
+        .unwrap_or_default();
+    let priority = app.event_priority.lock().ok().and_then(|p| *p);
+
+    let mut sections = vec![];
+    for stars in star_levels {
+        let loaded = app.all_stars || stars == app.star_level;
+        let base = if loaded {
+            app.encounters.clone()
+        } else {
+            base_encounters(stars)
+        };
+        let base_totals = version_totals(difficulty_table(stars), stars);
+        let base = base
+            .into_iter()
+            .filter(|(source, e)| {
+                matches!(source, EncounterSource::Base { stars: s, .. } if *s == stars)
+                    && matches_filter(*source, e)
+            })
+            .map(|(source, encounter)| IndexEntry {
+                source,
+                encounter,
+                chance: grid_chance(
+                    app.progress,
+                    stars,
+                    &chances_from_totals(&encounter, base_totals),
+                    app.game_version,
+                ),
+            })
+            .collect::<Vec<_>>();
+
+        let mut event = events
+            .iter()
+            .enumerate()
+            .filter(|(index, e)| {
+                e.species != 0
+                    && e.difficulty == stars
+                    && matches_filter(EncounterSource::Event { index: *index }, e)
+            })
+            .map(|(index, e)| IndexEntry {
+                source: EncounterSource::Event { index },
+                encounter: *e,
+                chance: if app.progress.unlocks(stars) {
+                    version_chance(
+                        &event_chances(e, &events, priority.as_ref()),
+                        app.game_version,
+                    )
+                } else {
+                    0.0
+                },
+            })
+            .collect::<Vec<_>>();
+        event.sort_by_key(|entry| {
+            (
+                entry.encounter.delivery_group_id,
+                names::english(&SPECIES, entry.encounter.species as usize),
+            )
+        });
+
+        if (app.all_stars || !loaded) && !(base.is_empty() && event.is_empty()) {
+            sections.push(Section {
+                header: Some(SectionHeader::Stars {
+                    stars,
+                    chance: app.progress.star_chance(stars),
+                }),
+                entries: vec![],
+            });
+        }
+        if !base.is_empty() {
+            sections.push(Section {
+                header: None,
+                entries: base,
+            });
+        }
+
+        let mut groups = event
+            .iter()
+            .map(|entry| entry.encounter.delivery_group_id)
+            .collect::<Vec<_>>();
+        groups.dedup();
+        for group in groups {
+            sections.push(Section {
+                header: Some(SectionHeader::EventGroup {
+                    group,
+                    chance: priority.map(|p| p.group_chance(group)),
+                }),
+                entries: event
+                    .iter()
+                    .filter(|entry| entry.encounter.delivery_group_id == group)
+                    .copied()
+                    .collect(),
+            });
+        }
+    }
+    sections
+}
@@ -0,0 +1,357 @@
+use crate::details_window::fixed_ability_index;
+use crate::i18n::tr;
+use crate::priority::EventPriority;
+use eframe::egui::{RichText, Ui};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use sv_raid_reader::{ExtraActionType, ItemTable, RaidEncounter, ABILITIES, MOVES, SPECIES};
+
+/// The files an event is published as. Downloads are named, dropped files are recognised by
+/// their size.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EventFile {
+    Encounters,
+    Priority,
+    FixedRewards,
+    LotteryRewards,
+}
+
+impl EventFile {
+    pub const ALL: [EventFile; 4] = [
+        EventFile::Encounters,
+        EventFile::Priority,
+        EventFile::FixedRewards,
+        EventFile::LotteryRewards,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EventFile::Encounters => "raid_enemy_array",
+            EventFile::Priority => "raid_priority_array",
+            EventFile::FixedRewards => "fixed_reward_item_array",
+            EventFile::LotteryRewards => "lottery_reward_item_array",
+        }
+    }
+
+    pub fn from_size(size: usize) -> Option<Self> {
+        match size {
+            30000 => Some(EventFile::Encounters),
+            88 => Some(EventFile::Priority),
+            27456 => Some(EventFile::FixedRewards),
+            53464 => Some(EventFile::LotteryRewards),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadError {
+    /// A dropped file could not be read.
+    Read {
+        file: String,
+        message: String,
+    },
+    /// A dropped file that is not one of the event files.
+    UnknownFile {
+        file: String,
+        size: usize,
+    },
+    Download {
+        file: EventFile,
+        message: String,
+    },
+    Decode {
+        file: EventFile,
+    },
+    /// Encounters that refer to species, moves or abilities this version doesn't know about,
+    /// usually because the event data is newer than the app. They are left out of the list.
+    UnknownEntries {
+        count: usize,
+    },
+    /// A shared link was made for a different event than the one that loaded.
+    OutdatedLink {
+        linked: i32,
+        loaded: i32,
+    },
+    /// A shared link points at an encounter the loaded event doesn't have.
+    MissingEncounter {
+        index: usize,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Read { file, message } => {
+                write!(f, "{} {}: {}", tr("Could not read"), file, message)
+            }
+            LoadError::UnknownFile { file, size } => write!(
+                f,
+                "{} ({} {}) {}",
+                file,
+                size,
+                tr("bytes"),
+                tr("is not an event data file")
+            ),
+            LoadError::Download { file, message } => write!(
+                f,
+                "{} {}: {}",
+                tr("Could not download"),
+                file.name(),
+                message
+            ),
+            LoadError::Decode { file } => write!(
+                f,
+                "{} {}",
+                file.name(),
+                tr("is damaged or in an unknown format")
+            ),
+            LoadError::UnknownEntries { count } => write!(
+                f,
+                "{}: {}",
+                tr("Skipped event encounters with data this version does not support"),
+                count
+            ),
+            LoadError::OutdatedLink { linked, loaded } => write!(
+                f,
+                "{} {}, {} {} {}",
+                tr("The link is for event"),
+                linked,
+                tr("but event"),
+                loaded,
+                tr("is loaded")
+            ),
+            LoadError::MissingEncounter { index } => {
+                write!(f, "{} {}", tr("The loaded event has no encounter"), index)
+            }
+        }
+    }
+}
+
+/// Whether every table index in the encounter is in range, so it can be shown without
+/// panicking.
+fn is_supported(encounter: &RaidEncounter) -> bool {
+    let known_move = |m: u16| (m as usize) < MOVES.len();
+    (encounter.species as usize) < SPECIES.len()
+        && encounter.moves.iter().all(|&m| known_move(m))
+        && encounter
+            .extra_actions
+            .iter()
+            .all(|a| !matches!(a.action, ExtraActionType::Move) || known_move(a.move_id))
+        && fixed_ability_index(encounter).map_or(true, |ability| ability < ABILITIES.len())
+}
+
+/// Handles to the shared event tables, cloned into download callbacks.
+#[derive(Clone)]
+pub struct EventData {
+    pub encounters: Arc<Mutex<Vec<RaidEncounter>>>,
+    pub priority: Arc<Mutex<Option<EventPriority>>>,
+    pub fixed_items: Arc<Mutex<ItemTable>>,
+    pub lottery_items: Arc<Mutex<ItemTable>>,
+    pub version: Arc<AtomicUsize>,
+    pub errors: Arc<Mutex<Vec<LoadError>>>,
+}
+
+impl EventData {
+    /// Decodes `bytes` as `file` and replaces the matching table.
+    pub fn load(&self, file: EventFile, bytes: &[u8]) -> Result<(), LoadError> {
+        let decode_error = || LoadError::Decode { file };
+        match file {
+            EventFile::Encounters => {
+                let table = sv_raid_reader::delivery_enemy_table_generated::root_as_delivery_raid_enemy_table_array(bytes)
+                    .map_err(|_| decode_error())?;
+                let mut encounters = table
+                    .values()
+                    .into_iter()
+                    .map(|t| t.raidEnemyInfo().into())
+                    .collect::<Vec<RaidEncounter>>();
+                // Blanking the species hides an encounter everywhere while keeping the indices
+                // links refer to.
+                let mut unsupported = 0;
+                for encounter in encounters.iter_mut() {
+                    if encounter.species != 0 && !is_supported(encounter) {
+                        encounter.species = 0;
+                        unsupported += 1;
+                    }
+                }
+                if unsupported > 0 {
+                    self.report(LoadError::UnknownEntries { count: unsupported });
+                }
+                if let Ok(mut current) = self.encounters.lock() {
+                    *current = encounters;
+                }
+            }
+            EventFile::Priority => {
+                let priority = EventPriority::parse(bytes).ok_or_else(decode_error)?;
+                if let Ok(mut current) = self.priority.lock() {
+                    *current = Some(priority);
+                }
+            }
+            EventFile::FixedRewards => {
+                let table = sv_raid_reader::raid_fixed_reward_item_generated::root_as_raid_fixed_reward_item_array(bytes)
+                    .map_err(|_| decode_error())?;
+                if let Ok(mut current) = self.fixed_items.lock() {
+                    *current = table.into();
+                }
+            }
+            EventFile::LotteryRewards => {
+                let table = sv_raid_reader::raid_lottery_reward_item_generated::root_as_raid_lottery_reward_item_array(bytes)
+                    .map_err(|_| decode_error())?;
+                if let Ok(mut current) = self.lottery_items.lock() {
+                    *current = table.into();
+                }
+            }
+        }
+        self.version.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn report(&self, error: LoadError) {
+        if let Ok(mut errors) = self.errors.lock() {
+            errors.push(error);
+        }
+    }
+
+    /// Whether downloading or decoding `file` failed since the errors were last dismissed.
+    pub fn failed(&self, file: EventFile) -> bool {
+        self.errors.lock().map_or(false, |errors| {
+            errors.iter().any(|error| match error {
+                LoadError::Download { file: f, .. } | LoadError::Decode { file: f } => *f == file,
+                _ => false,
+            })
+        })
+    }
+}
+
+/// Lists problems from loading event data with a button to dismiss them.
+pub fn errors_ui(ui: &mut Ui, errors: &Mutex<Vec<LoadError>>) {
+    let mut errors = match errors.lock() {
+        Ok(errors) => errors,
+        Err(_) => return,
+    };
+    if errors.is_empty() {
+        return;
+    }
+    let color = ui.visuals().error_fg_color;
+    for error in errors.iter() {
+        ui.label(RichText::new(error.to_string()).color(color));
+    }
+    if ui.small_button(tr("Dismiss")).clicked() {
+        errors.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::difficulty_table;
+    use std::collections::HashMap;
+
+    fn event_data() -> EventData {
+        EventData {
+            encounters: Default::default(),
+            priority: Default::default(),
+            fixed_items: Arc::new(Mutex::new(ItemTable(HashMap::default()))),
+            lottery_items: Arc::new(Mutex::new(ItemTable(HashMap::default()))),
+            version: Default::default(),
+            errors: Default::default(),
+        }
+    }
+
+    #[test]
+    fn recognises_files_by_size() {
+        let files = [
+            (
+                EventFile::Encounters,
+                &include_bytes!("../raid_data/latest_delivery")[..],
+            ),
+            (
+                EventFile::Priority,
+                &include_bytes!("../raid_data/latest_priority")[..],
+            ),
+            (
+                EventFile::FixedRewards,
+                &include_bytes!("../raid_data/latest_fixed_reward_item_array")[..],
+            ),
+            (
+                EventFile::LotteryRewards,
+                &include_bytes!("../raid_data/latest_lottery_reward_item_array")[..],
+            ),
+        ];
+        for (file, bytes) in files {
+            assert_eq!(EventFile::from_size(bytes.len()), Some(file));
+        }
+        assert_eq!(EventFile::from_size(0), None);
+        assert_eq!(EventFile::from_size(30001), None);
+    }
+
+    #[test]
+    fn flags_out_of_range_entries() {
+        let encounter = difficulty_table(1)[0];
+        assert!(is_supported(&encounter));
+
+        let mut species = encounter;
+        species.species = u16::MAX;
+        assert!(!is_supported(&species));
+
+        let mut moves = encounter;
+        moves.moves[0] = u16::MAX;
+        assert!(!is_supported(&moves));
+    }
+
+    #[test]
+    fn loads_published_priority() {
+        let data = event_data();
+        let bytes = include_bytes!("../raid_data/latest_priority");
+        assert_eq!(data.load(EventFile::Priority, bytes), Ok(()));
+        assert_eq!(data.version.load(Ordering::Relaxed), 1);
+        assert!(data.priority.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn reports_damaged_files() {
+        let data = event_data();
+        assert_eq!(
+            data.load(EventFile::Priority, &[0; 3]),
+            Err(LoadError::Decode {
+                file: EventFile::Priority
+            })
+        );
+        assert_eq!(data.version.load(Ordering::Relaxed), 0);
+
+        data.report(LoadError::Download {
+            file: EventFile::Encounters,
+            message: "timed out".to_string(),
+        });
+        assert!(data.failed(EventFile::Encounters));
+        assert!(!data.failed(EventFile::Priority));
+    }
+
+    #[test]
+    fn describes_errors() {
+        assert_eq!(
+            LoadError::UnknownFile {
+                file: "notes.txt".to_string(),
+                size: 12
+            }
+            .to_string(),
+            "notes.txt (12 bytes) is not an event data file"
+        );
+        assert_eq!(
+            LoadError::Decode {
+                file: EventFile::Priority
+            }
+            .to_string(),
+            "raid_priority_array is damaged or in an unknown format"
+        );
+        assert_eq!(
+            LoadError::OutdatedLink {
+                linked: 20221202,
+                loaded: 20230105
+            }
+            .to_string(),
+            "The link is for event 20221202, but event 20230105 is loaded"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use crate::i18n::tr;
+use crate::names;
+use crate::rewards::Reward;
+use crate::types::GEM_TYPES;
+use crate::version::{version_selector, GameVersion};
+use eframe::egui;
+use eframe::egui::Ui;
+use sv_raid_reader::{
+    GemType, Gender, IvType, RaidEncounter, Seikaku, ShinyType, Tokusei, MOVES, NATURES,
+};
+
+pub const ABILITY_MODES: [(Tokusei, &str); 5] = [
+    (Tokusei::Random12, "Random 1/2"),
+    (Tokusei::Random123, "Random 1/2/H"),
+    (Tokusei::Set1, "Ability 1"),
+    (Tokusei::Set2, "Ability 2"),
+    (Tokusei::Set3, "Hidden Ability"),
+];
+
+pub const GENDERS: [(Gender, &str); 3] = [
+    (Gender::Random, "Random"),
+    (Gender::Male, "Male"),
+    (Gender::Female, "Female"),
+];
+
+pub const SHINY_TYPES: [(ShinyType, &str); 3] = [
+    (ShinyType::Random, "Random"),
+    (ShinyType::Never, "Shiny Locked"),
+    (ShinyType::Always, "Always Shiny"),
+];
+
+/// Number of IVs an encounter is guaranteed to have at 31.
+pub fn guaranteed_perfect_ivs(encounter: &RaidEncounter) -> u8 {
+    match encounter.iv_type {
+        IvType::Random => 0,
+        IvType::VNum => encounter.flawless_ivs,
+        IvType::Value => encounter.ivs.iter().filter(|&&iv| iv == 31).count() as u8,
+    }
+}
+
+/// Filters that apply on top of the species name filter. `None` and empty strings match anything.
+#[derive(Clone, Default, PartialEq)]
+pub struct EncounterFilter {
+    pub tera_type: Option<GemType>,
+    pub ability: Option<Tokusei>,
+    pub nature: Option<usize>,
+    pub gender: Option<Gender>,
+    pub shiny: Option<ShinyType>,
+    pub perfect_ivs: u8,
+    pub move_name: String,
+    pub item_name: String,
+}
+
+impl EncounterFilter {
+    pub fn is_active(&self) -> bool {
+        self.tera_type.is_some()
+            || self.ability.is_some()
+            || self.nature.is_some()
+            || self.gender.is_some()
+            || self.shiny.is_some()
+            || self.perfect_ivs > 0
+            || !self.move_name.is_empty()
+            || !self.item_name.is_empty()
+    }
+
+    /// Rewards are only resolved when an item filter is set since they need the item tables.
+    pub fn matches(
+        &self,
+        encounter: &RaidEncounter,
+        rewards: impl FnOnce() -> Vec<Reward>,
+    ) -> bool {
+        if self.tera_type.map_or(false, |t| t != encounter.gem_type)
+            || self.ability.map_or(false, |a| a != encounter.tokusei)
+            || self.gender.map_or(false, |g| g != encounter.gender)
+            || self.shiny.map_or(false, |s| s != encounter.shiny)
+            || guaranteed_perfect_ivs(encounter) < self.perfect_ivs
+        {
+            return false;
+        }
+
+        if let Some(nature) = self.nature {
+            if encounter.seikaku == Seikaku::Random || encounter.seikaku as usize - 1 != nature {
+                return false;
+            }
+        }
+
+        if !self.move_name.is_empty() {
+            let move_name = self.move_name.to_lowercase();
+            if !encounter.moves.iter().filter(|&&m| m != 0).any(|&m| {
+                let english = names::english(&MOVES, m as usize);
+                names::name_contains(english, names::move_name(m), &move_name)
+            }) {
+                return false;
+            }
+        }
+
+        if !self.item_name.is_empty() {
+            let item_name = self.item_name.to_lowercase();
+            if !rewards()
+                .iter()
+                .any(|r| names::name_contains(r.name(), r.local_name(), &item_name))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// The version row edits the app-wide version selection, which also picks the spawn
+    /// chances shown, so it isn't part of the filter itself.
+    pub fn ui(&mut self, ui: &mut Ui, version: &mut Option<GameVersion>) {
+        egui::Grid::new("advanced_filters")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr("Tera Type:"));
+                combo(
+                    ui,
+                    "filter_tera",
+                    &mut self.tera_type,
+                    &GEM_TYPES.map(|t| (t, names::gem_type(t))),
+                );
+                ui.end_row();
+
+                ui.label(tr("Ability:"));
+                combo(
+                    ui,
+                    "filter_ability",
+                    &mut self.ability,
+                    &ABILITY_MODES.map(|(mode, name)| (mode, tr(name))),
+                );
+                ui.end_row();
+
+                ui.label(tr("Nature:"));
+                let natures = (0..NATURES.len())
+                    .map(|i| (i, names::nature(i)))
+                    .collect::<Vec<_>>();
+                combo(ui, "filter_nature", &mut self.nature, &natures);
+                ui.end_row();
+
+                ui.label(tr("Gender:"));
+                combo(
+                    ui,
+                    "filter_gender",
+                    &mut self.gender,
+                    &GENDERS.map(|(gender, name)| (gender, tr(name))),
+                );
+                ui.end_row();
+
+                ui.label(tr("Shiny:"));
+                combo(
+                    ui,
+                    "filter_shiny",
+                    &mut self.shiny,
+                    &SHINY_TYPES.map(|(shiny, name)| (shiny, tr(name))),
+                );
+                ui.end_row();
+
+                ui.label(tr("Perfect IVs:"));
+                ui.add(egui::DragValue::new(&mut self.perfect_ivs).clamp_range(0..=6));
+                ui.end_row();
+
+                ui.label(tr("Version:"));
+                ui.horizontal(|ui| version_selector(ui, version));
+                ui.end_row();
+
+                ui.label(tr("Move:"));
+                ui.text_edit_singleline(&mut self.move_name);
+                ui.end_row();
+
+                ui.label(tr("Drops:"));
+                ui.text_edit_singleline(&mut self.item_name);
+                ui.end_row();
+            });
+
+        if ui.button(tr("Clear Filters")).clicked() {
+            *self = Self::default();
+            *version = None;
+        }
+    }
+}
+
+fn combo<T: Copy + PartialEq>(ui: &mut Ui, id: &str, value: &mut Option<T>, options: &[(T, &str)]) {
+    let selected = options
+        .iter()
+        .find(|(option, _)| Some(*option) == *value)
+        .map_or(tr("Any"), |(_, name)| *name);
+    egui::ComboBox::from_id_source(id)
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(value, None, tr("Any"));
+            for (option, name) in options.iter() {
+                ui.selectable_value(value, Some(*option), *name);
+            }
+        });
+}
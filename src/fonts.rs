@@ -0,0 +1,82 @@
+use crate::names::Language;
+use eframe::egui::{Context, FontData, FontDefinitions, FontFamily};
+
+const FONT_NAME: &str = "cjk";
+
+/// Fonts already installed with the OS that cover the language, checked in order.
+#[cfg(not(target_arch = "wasm32"))]
+fn system_fonts(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Japanese => &[
+            "C:\\Windows\\Fonts\\YuGothR.ttc",
+            "C:\\Windows\\Fonts\\meiryo.ttc",
+            "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+            "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+        ],
+        Language::Korean => &[
+            "C:\\Windows\\Fonts\\malgun.ttf",
+            "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+            "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+        ],
+        Language::Chinese => &[
+            "C:\\Windows\\Fonts\\msyh.ttc",
+            "/System/Library/Fonts/PingFang.ttc",
+            "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+        ],
+        _ => &[],
+    }
+}
+
+/// Noto Sans subset for the language, fetched when no system font is available. The web
+/// build's service worker keeps the download, so it is only fetched once.
+fn font_url(language: Language) -> Option<&'static str> {
+    match language {
+        Language::Japanese => Some("https://raw.githubusercontent.com/googlefonts/noto-cjk/main/Sans/SubsetOTF/JP/NotoSansJP-Regular.otf"),
+        Language::Korean => Some("https://raw.githubusercontent.com/googlefonts/noto-cjk/main/Sans/SubsetOTF/KR/NotoSansKR-Regular.otf"),
+        Language::Chinese => Some("https://raw.githubusercontent.com/googlefonts/noto-cjk/main/Sans/SubsetOTF/SC/NotoSansSC-Regular.otf"),
+        _ => None,
+    }
+}
+
+/// Adds `bytes` as a fallback after egui's default fonts so CJK text renders.
+fn install(ctx: &Context, bytes: Vec<u8>) {
+    let mut fonts = FontDefinitions::default();
+    fonts
+        .font_data
+        .insert(FONT_NAME.to_string(), FontData::from_owned(bytes));
+    for family in [FontFamily::Proportional, FontFamily::Monospace] {
+        fonts
+            .families
+            .entry(family)
+            .or_default()
+            .push(FONT_NAME.to_string());
+    }
+    ctx.set_fonts(fonts);
+}
+
+/// Registers a font that can draw the language's script. Languages egui's default fonts
+/// already cover are left alone.
+pub fn load_font(language: Language, ctx: &Context) {
+    #[cfg(not(target_arch = "wasm32"))]
+    for path in system_fonts(language) {
+        if let Ok(bytes) = std::fs::read(path) {
+            install(ctx, bytes);
+            return;
+        }
+    }
+
+    if let Some(url) = font_url(language) {
+        let ctx = ctx.clone();
+        ehttp::fetch(ehttp::Request::get(url), move |response| {
+            if let Ok(response) = response {
+                if response.ok {
+                    install(&ctx, response.bytes);
+                    ctx.request_repaint();
+                }
+            }
+        });
+    }
+}
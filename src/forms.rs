@@ -0,0 +1,126 @@
+use crate::i18n::tr;
+use crate::names;
+use sv_raid_reader::SPECIES;
+
+/// Showdown suffixes for the alternate forms that can show up in raids.
+pub fn form_suffix(species: u16, form: u8) -> Option<&'static str> {
+    let suffix = match (species, form) {
+        (_, 0) => return None,
+        (26 | 27 | 28 | 37 | 38 | 50 | 51 | 52 | 53 | 88 | 89 | 103 | 105, 1) => "Alola",
+        (52, 2) | (79 | 199, 1) | (80, 2) => "Galar",
+        (58 | 59 | 100 | 101 | 157 | 211 | 215 | 503 | 549 | 570 | 571 | 628, 1) => "Hisui",
+        (705 | 706 | 713 | 724, 1) => "Hisui",
+        (128, 1) => "Paldea-Combat",
+        (128, 2) => "Paldea-Blaze",
+        (128, 3) => "Paldea-Aqua",
+        (194, 1) => "Paldea",
+        (479, 1) => "Heat",
+        (479, 2) => "Wash",
+        (479, 3) => "Frost",
+        (479, 4) => "Fan",
+        (479, 5) => "Mow",
+        (550, 1) => "Blue-Striped",
+        (550, 2) => "White-Striped",
+        (678 | 876 | 902 | 916, 1) => "F",
+        (741, 1) => "Pom-Pom",
+        (741, 2) => "Pa'u",
+        (741, 3) => "Sensu",
+        (745, 1) => "Midnight",
+        (745, 2) => "Dusk",
+        (849, 1) => "Low-Key",
+        (925, 1) => "Four",
+        (931, 1) => "Blue",
+        (931, 2) => "Yellow",
+        (931, 3) => "White",
+        (964, 1) => "Hero",
+        (978, 1) => "Droopy",
+        (978, 2) => "Stretchy",
+        (982, 1) => "Three-Segment",
+        (999, 1) => "Roaming",
+        _ => return None,
+    };
+    Some(suffix)
+}
+
+/// Readable English name of a known alternate form, such as "Paldean Blaze Breed" or
+/// "Pom-Pom Style".
+fn english_form_name(species: u16, form: u8) -> Option<&'static str> {
+    let name = match form_suffix(species, form)? {
+        "Alola" => "Alolan Form",
+        "Galar" => "Galarian Form",
+        "Hisui" => "Hisuian Form",
+        "Paldea" => "Paldean Form",
+        "Paldea-Combat" => "Paldean Combat Breed",
+        "Paldea-Blaze" => "Paldean Blaze Breed",
+        "Paldea-Aqua" => "Paldean Aqua Breed",
+        "Heat" => "Heat Rotom",
+        "Wash" => "Wash Rotom",
+        "Frost" => "Frost Rotom",
+        "Fan" => "Fan Rotom",
+        "Mow" => "Mow Rotom",
+        "Blue-Striped" => "Blue-Striped Form",
+        "White-Striped" => "White-Striped Form",
+        "F" => "Female",
+        "Pom-Pom" => "Pom-Pom Style",
+        "Pa'u" => "Pa'u Style",
+        "Sensu" => "Sensu Style",
+        "Midnight" => "Midnight Form",
+        "Dusk" => "Dusk Form",
+        "Low-Key" => "Low Key Form",
+        "Four" => "Family of Four",
+        "Blue" => "Blue Plumage",
+        "Yellow" => "Yellow Plumage",
+        "White" => "White Plumage",
+        "Hero" => "Hero Form",
+        "Droopy" => "Droopy Form",
+        "Stretchy" => "Stretchy Form",
+        "Three-Segment" => "Three-Segment Form",
+        "Roaming" => "Roaming Form",
+        other => other,
+    };
+    Some(name)
+}
+
+/// Form name in the selected language, or "Form N" for alternate forms this version doesn't
+/// know, so they can still be told apart from the base form. The base form has no name.
+pub fn form_name(species: u16, form: u8) -> Option<String> {
+    if form == 0 {
+        return None;
+    }
+    Some(match english_form_name(species, form) {
+        Some(name) => tr(name).to_string(),
+        None => format!("{} {}", tr("Form"), form),
+    })
+}
+
+/// Species name in the selected language with its form, e.g. "Tauros (Paldean Aqua Breed)".
+pub fn display_name(species: u16, form: u8) -> String {
+    match form_name(species, form) {
+        Some(form) => format!("{} ({})", names::species(species), form),
+        None => names::species(species).to_string(),
+    }
+}
+
+/// Lowercase English and translated species and form names joined together, for species
+/// searches.
+pub fn search_text(species: u16, form: u8) -> String {
+    let mut text = format!(
+        "{}\n{}",
+        names::english(&SPECIES, species as usize),
+        names::species(species)
+    );
+    if let Some(english) = english_form_name(species, form) {
+        text.push('\n');
+        text.push_str(english);
+    }
+    if let Some(form) = form_name(species, form) {
+        text.push('\n');
+        text.push_str(&form);
+    }
+    text.to_lowercase()
+}
+
+/// Whether a lowercase search term is part of the species' English, translated or form name.
+pub fn species_matches(species: u16, form: u8, term: &str) -> bool {
+    search_text(species, form).contains(term)
+}
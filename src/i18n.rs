@@ -0,0 +1,46 @@
+use crate::names::{language, Language};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Message catalog for a language. Each line is `English text = translation`, lines starting
+/// with `#` are comments.
+fn catalog(language: Language) -> Option<&'static str> {
+    let text = match language {
+        Language::English => return None,
+        Language::Japanese => include_str!("../resources/locales/ja.txt"),
+        Language::French => include_str!("../resources/locales/fr.txt"),
+        Language::German => include_str!("../resources/locales/de.txt"),
+        Language::Spanish => include_str!("../resources/locales/es.txt"),
+        Language::Italian => include_str!("../resources/locales/it.txt"),
+        Language::Korean => include_str!("../resources/locales/ko.txt"),
+        Language::Chinese => include_str!("../resources/locales/zh.txt"),
+    };
+    Some(text)
+}
+
+/// Catalogs split into `English => translation` maps, each the first time it is used.
+static CATALOGS: Mutex<Option<HashMap<Language, HashMap<&'static str, &'static str>>>> =
+    Mutex::new(None);
+
+/// Translates a UI string into the selected language, keeping the English text when the
+/// catalog has no entry for it.
+pub fn tr(text: &'static str) -> &'static str {
+    let language = language();
+    let mut catalogs = CATALOGS.lock().unwrap_or_else(|e| e.into_inner());
+    catalogs
+        .get_or_insert_with(HashMap::new)
+        .entry(language)
+        .or_insert_with(|| {
+            catalog(language).map_or_else(HashMap::new, |catalog| {
+                catalog
+                    .lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .filter_map(|line| line.split_once(" = "))
+                    .map(|(key, translation)| (key, translation.trim()))
+                    .collect()
+            })
+        })
+        .get(text)
+        .copied()
+        .unwrap_or(text)
+}
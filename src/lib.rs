@@ -1,10 +1,34 @@
 use eframe::egui::Context;
 
 pub mod app;
+mod compare;
+pub mod damage;
+mod deep_link;
 pub mod details_window;
+pub mod encounter_details;
 mod encounter_grid;
+mod encounter_index;
+mod event_data;
+pub mod filters;
+mod fonts;
+pub mod forms;
+mod i18n;
+pub mod matchup;
 mod mobile_bar;
+pub mod names;
+pub mod priority;
+pub mod progress;
+pub mod query;
+pub mod rewards;
+pub mod seed;
+pub mod showdown;
 mod side_panel;
+mod species_page;
+pub mod sprites;
+pub mod stats;
+pub mod teams;
+pub mod types;
+pub mod version;
 
 fn is_mobile(ctx: &Context) -> bool {
     let screen_size = ctx.input().screen_rect().size();
@@ -0,0 +1,256 @@
+use crate::encounter_details::EncounterDetails;
+use crate::i18n::tr;
+use crate::names;
+use crate::types::{
+    base_types, boss_move_type, effectiveness, effectiveness_against, move_kind, MoveType,
+    TYPE_COUNT,
+};
+use eframe::egui;
+use eframe::egui::{Color32, RichText, Ui};
+use sv_raid_reader::{personal_table, ExtraActionType, SPECIES};
+
+const COUNTER_COUNT: usize = 10;
+
+/// A species suggested as a counter, scored by how hard its STAB hits the boss, how little it
+/// takes from the boss' moves, and its better attacking stat.
+#[derive(Clone)]
+pub struct Counter {
+    pub species: u16,
+    pub types: Vec<usize>,
+    pub offense: f32,
+    pub worst_taken: f32,
+    pub attack: u16,
+    pub score: f32,
+}
+
+impl Counter {
+    pub fn label(&self) -> String {
+        format!(
+            "{} ({}): {}x {}, {} {}x, {} {}",
+            names::species(self.species),
+            type_names(&self.types),
+            self.offense,
+            tr("STAB"),
+            tr("takes at most"),
+            self.worst_taken,
+            tr("best attack"),
+            self.attack
+        )
+    }
+}
+
+/// Type matchups of a raid boss: what hits it hard and what walls its moves.
+#[derive(Clone)]
+pub struct Matchup {
+    pub defense_types: Vec<usize>,
+    /// `None` for a random Tera type.
+    pub tera_type: Option<usize>,
+    /// Every move the boss can use with its type, `None` for status moves and moves whose type
+    /// isn't known ahead of the battle.
+    pub moves: Vec<(u16, Option<usize>)>,
+    pub counters: Vec<Counter>,
+}
+
+fn type_names(types: &[usize]) -> String {
+    types
+        .iter()
+        .map(|&t| names::type_name(t))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Highest multiplier the given moves deal to `types`, 0 when none of them do damage.
+fn worst_taken(move_types: &[usize], types: &[usize]) -> f32 {
+    move_types
+        .iter()
+        .map(|&m| effectiveness_against(m, types))
+        .fold(0.0, f32::max)
+}
+
+/// Type of a boss move for display, explaining why it is missing for moves whose type isn't
+/// fixed.
+pub fn move_type_label(move_id: u16, tera_type: Option<usize>) -> &'static str {
+    match move_kind(move_id) {
+        Some(MoveType::Tera) if tera_type.is_none() => tr("Tera type"),
+        Some(MoveType::Variable) => tr("type varies"),
+        Some(kind) => kind.resolve(tera_type).map_or("", names::type_name),
+        None => tr("status or unknown"),
+    }
+}
+
+impl Matchup {
+    pub fn new(details: &EncounterDetails) -> Self {
+        let mut move_ids = details.moves.clone();
+        for action in details.extra_actions.iter() {
+            if matches!(action.action, ExtraActionType::Move)
+                && action.move_id != 0
+                && !move_ids.contains(&action.move_id)
+            {
+                move_ids.push(action.move_id);
+            }
+        }
+        let tera_type = details.tera_type;
+        let moves = move_ids
+            .into_iter()
+            .map(|m| (m, boss_move_type(m, tera_type)))
+            .collect::<Vec<_>>();
+
+        let mut matchup = Self {
+            defense_types: details.defense_types(),
+            tera_type,
+            moves,
+            counters: vec![],
+        };
+        matchup.counters = matchup.rank_counters();
+        matchup
+    }
+
+    /// Distinct types of the boss' damaging moves.
+    pub fn boss_move_types(&self) -> Vec<usize> {
+        let mut types = self
+            .moves
+            .iter()
+            .filter_map(|&(_, t)| t)
+            .collect::<Vec<_>>();
+        types.sort_unstable();
+        types.dedup();
+        types
+    }
+
+    /// Attacking types that are super effective against the boss, with their multiplier.
+    pub fn super_effective(&self) -> Vec<(usize, f32)> {
+        (0..TYPE_COUNT)
+            .map(|t| (t, effectiveness_against(t, &self.defense_types)))
+            .filter(|&(_, e)| e > 1.0)
+            .collect()
+    }
+
+    /// Defensive types that resist or are immune to every damaging move the boss has.
+    pub fn resisting_types(&self) -> Vec<usize> {
+        let move_types = self.boss_move_types();
+        if move_types.is_empty() {
+            return vec![];
+        }
+        (0..TYPE_COUNT)
+            .filter(|&t| move_types.iter().all(|&m| effectiveness(m, t) < 1.0))
+            .collect()
+    }
+
+    /// Ranks base forms from the personal table. Species with no base stats are skipped, but
+    /// the table does not say which are obtainable, so unavailable species can show up.
+    fn rank_counters(&self) -> Vec<Counter> {
+        let move_types = self.boss_move_types();
+        let mut counters = (1..SPECIES.len() as u16)
+            .filter_map(|species| {
+                let personal = personal_table::SV.get_form_entry(species as usize, 0);
+                let stats = personal.stats();
+                if stats.iter().all(|&s| s == 0) {
+                    return None;
+                }
+                let types = base_types(species, 0);
+                let offense = types
+                    .iter()
+                    .map(|&t| effectiveness_against(t, &self.defense_types))
+                    .fold(0.0, f32::max);
+                if offense <= 1.0 {
+                    return None;
+                }
+                let worst_taken = worst_taken(&move_types, &types);
+                // Personal data stores stats as HP/Atk/Def/Spe/SpA/SpD.
+                let attack = stats[1].max(stats[4]) as u16;
+                let score = offense * attack as f32 / worst_taken.max(0.25);
+                Some(Counter {
+                    species,
+                    types,
+                    offense,
+                    worst_taken,
+                    attack,
+                    score,
+                })
+            })
+            .collect::<Vec<_>>();
+        counters.sort_by(|a, b| b.score.total_cmp(&a.score));
+        counters.truncate(COUNTER_COUNT);
+        counters
+    }
+
+    pub fn ui(&self, ui: &mut Ui) {
+        ui.label(format!(
+            "{} {}",
+            tr("Defends as:"),
+            type_names(&self.defense_types)
+        ));
+
+        ui.label(tr("Boss moves:"));
+        for &(move_id, _) in self.moves.iter() {
+            ui.label(format!(
+                "  {} ({})",
+                names::move_name(move_id),
+                move_type_label(move_id, self.tera_type)
+            ));
+        }
+
+        ui.add_space(5.0);
+        egui::CollapsingHeader::new(tr("Type Chart")).show(ui, |ui| {
+            egui::Grid::new("matchup_chart")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(tr("Attacking type"));
+                    ui.label(tr("vs boss"));
+                    ui.end_row();
+                    for t in 0..TYPE_COUNT {
+                        let e = effectiveness_against(t, &self.defense_types);
+                        let color = if e > 1.0 {
+                            Color32::GREEN
+                        } else if e == 0.0 {
+                            Color32::GRAY
+                        } else if e < 1.0 {
+                            Color32::RED
+                        } else {
+                            ui.visuals().text_color()
+                        };
+                        ui.label(names::type_name(t));
+                        ui.label(RichText::new(format!("{}x", e)).color(color));
+                        ui.end_row();
+                    }
+                });
+        });
+
+        let super_effective = self.super_effective();
+        ui.label(format!(
+            "{} {}",
+            tr("Super effective:"),
+            if super_effective.is_empty() {
+                tr("None").to_string()
+            } else {
+                super_effective
+                    .iter()
+                    .map(|&(t, e)| format!("{} ({}x)", names::type_name(t), e))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        ));
+
+        let resisting = self.resisting_types();
+        ui.label(format!(
+            "{} {}",
+            tr("Resists all boss moves:"),
+            if resisting.is_empty() {
+                tr("None").to_string()
+            } else {
+                type_names(&resisting).replace('/', ", ")
+            }
+        ));
+
+        ui.add_space(5.0);
+        egui::CollapsingHeader::new(tr("Counter Candidates")).show(ui, |ui| {
+            if self.counters.is_empty() {
+                ui.label(tr("None"));
+            }
+            for counter in self.counters.iter() {
+                ui.label(counter.label());
+            }
+            ui.small(tr("Ranked by typing and base stats only."));
+        });
+    }
+}
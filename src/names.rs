@@ -0,0 +1,240 @@
+use crate::types::gem_type_index;
+use eframe::egui;
+use eframe::egui::Ui;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use sv_raid_reader::{GemType, ABILITIES, ITEMS, MOVES, NATURES, SPECIES, TYPES};
+
+/// Language game-data names are shown in. English uses the tables from `sv_raid_reader`,
+/// other languages read `resources/names/<code>/<table>.txt` and fall back to English for
+/// any table or line that is missing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Language {
+    English,
+    Japanese,
+    French,
+    German,
+    Spanish,
+    Italian,
+    Korean,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 8] = [
+        Language::English,
+        Language::Japanese,
+        Language::French,
+        Language::German,
+        Language::Spanish,
+        Language::Italian,
+        Language::Korean,
+        Language::Chinese,
+    ];
+
+    /// The language's own name for itself.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Japanese => "日本語",
+            Language::French => "Français",
+            Language::German => "Deutsch",
+            Language::Spanish => "Español",
+            Language::Italian => "Italiano",
+            Language::Korean => "한국어",
+            Language::Chinese => "中文",
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Japanese => "ja",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Spanish => "es",
+            Language::Italian => "it",
+            Language::Korean => "ko",
+            Language::Chinese => "zh",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.code() == code)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Table {
+    Species,
+    Abilities,
+    Natures,
+    Types,
+    Items,
+    Moves,
+}
+
+/// Key the selected language is stored under in eframe's storage.
+pub const LANGUAGE_KEY: &str = "language";
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+pub fn language() -> Language {
+    Language::ALL[LANGUAGE.load(Ordering::Relaxed) as usize]
+}
+
+pub fn set_language(language: Language) {
+    let index = Language::ALL
+        .iter()
+        .position(|&l| l == language)
+        .unwrap_or(0);
+    LANGUAGE.store(index as u8, Ordering::Relaxed);
+}
+
+/// Bundled name tables, one name per line in the same order as the English table.
+fn resource(language: Language, table: Table) -> Option<&'static str> {
+    use Language::*;
+    use Table::*;
+    let text = match (language, table) {
+        (Japanese, Natures) => include_str!("../resources/names/ja/natures.txt"),
+        (Japanese, Types) => include_str!("../resources/names/ja/types.txt"),
+        (French, Natures) => include_str!("../resources/names/fr/natures.txt"),
+        (French, Types) => include_str!("../resources/names/fr/types.txt"),
+        (German, Natures) => include_str!("../resources/names/de/natures.txt"),
+        (German, Types) => include_str!("../resources/names/de/types.txt"),
+        (Spanish, Natures) => include_str!("../resources/names/es/natures.txt"),
+        (Spanish, Types) => include_str!("../resources/names/es/types.txt"),
+        (Italian, Natures) => include_str!("../resources/names/it/natures.txt"),
+        (Italian, Types) => include_str!("../resources/names/it/types.txt"),
+        (Korean, Natures) => include_str!("../resources/names/ko/natures.txt"),
+        (Korean, Types) => include_str!("../resources/names/ko/types.txt"),
+        (Chinese, Natures) => include_str!("../resources/names/zh/natures.txt"),
+        (Chinese, Types) => include_str!("../resources/names/zh/types.txt"),
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// Bundled tables split into lines, each the first time it is used.
+static TABLES: Mutex<Option<HashMap<(Language, Table), Vec<&'static str>>>> = Mutex::new(None);
+
+/// Name for `index` in the selected language. Indices past the end of the English table, as
+/// in data newer than this version, give "???" rather than panicking.
+fn lookup(table: Table, index: usize, english: &[&'static str]) -> &'static str {
+    let english = match english.get(index) {
+        Some(&name) => name,
+        None => return "???",
+    };
+    let language = language();
+    let mut tables = TABLES.lock().unwrap_or_else(|e| e.into_inner());
+    tables
+        .get_or_insert_with(HashMap::new)
+        .entry((language, table))
+        .or_insert_with(|| {
+            resource(language, table)
+                .map_or_else(Vec::new, |text| text.lines().map(str::trim).collect())
+        })
+        .get(index)
+        .copied()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(english)
+}
+
+/// English name for `index`, or "???" past the end of the table as in data newer than this
+/// version.
+pub fn english(table: &[&'static str], index: usize) -> &'static str {
+    table.get(index).copied().unwrap_or("???")
+}
+
+pub fn species(species: u16) -> &'static str {
+    lookup(Table::Species, species as usize, &SPECIES)
+}
+
+pub fn ability(ability: usize) -> &'static str {
+    lookup(Table::Abilities, ability, &ABILITIES)
+}
+
+pub fn nature(nature: usize) -> &'static str {
+    lookup(Table::Natures, nature, &NATURES)
+}
+
+pub fn type_name(type_index: usize) -> &'static str {
+    lookup(Table::Types, type_index, &TYPES)
+}
+
+pub fn item(item: usize) -> &'static str {
+    lookup(Table::Items, item, &ITEMS)
+}
+
+pub fn move_name(move_id: u16) -> &'static str {
+    lookup(Table::Moves, move_id as usize, &MOVES)
+}
+
+/// Whether a lowercase search term is part of the English or the selected language's name.
+pub fn name_contains(english: &str, localized: &str, term: &str) -> bool {
+    english.to_lowercase().contains(term) || localized.to_lowercase().contains(term)
+}
+
+/// Language picker. Returns true when the language changed so callers can rebuild any
+/// names they formatted ahead of time.
+pub fn language_selector(ui: &mut Ui) -> bool {
+    let mut selected = language();
+    egui::ComboBox::from_id_source("language")
+        .selected_text(selected.name())
+        .show_ui(ui, |ui| {
+            for l in Language::ALL {
+                ui.selectable_value(&mut selected, l, l.name());
+            }
+        });
+    let changed = selected != language();
+    set_language(selected);
+    changed
+}
+
+/// Localized Tera type, or "Random" for encounters without a set Tera type.
+pub fn gem_type(gem_type: GemType) -> &'static str {
+    gem_type_index(gem_type).map_or("Random", type_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_tables_line_up_with_english() {
+        let tables: [(Table, &[&str]); 6] = [
+            (Table::Species, &SPECIES[..]),
+            (Table::Abilities, &ABILITIES[..]),
+            (Table::Natures, &NATURES[..]),
+            (Table::Types, &TYPES[..]),
+            (Table::Items, &ITEMS[..]),
+            (Table::Moves, &MOVES[..]),
+        ];
+        let mut missing = vec![];
+        for language in Language::ALL
+            .into_iter()
+            .filter(|&l| l != Language::English)
+        {
+            for (table, english) in tables {
+                match resource(language, table) {
+                    Some(text) => assert!(
+                        text.lines().count() <= english.len(),
+                        "{:?} {:?} has more lines than the English table",
+                        language,
+                        table
+                    ),
+                    None => missing.push((language, table)),
+                }
+            }
+        }
+        assert!(missing.is_empty(), "Tables not bundled: {:?}", missing);
+    }
+
+    #[test]
+    fn out_of_range_names_do_not_panic() {
+        assert_eq!(english(&SPECIES, 25), "Pikachu");
+        assert_eq!(english(&SPECIES, usize::MAX), "???");
+        assert_eq!(species(u16::MAX), "???");
+    }
+}
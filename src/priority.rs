@@ -0,0 +1,191 @@
+use crate::version::{available_in, GameVersion, SpawnChance};
+use sv_raid_reader::RaidEncounter;
+
+pub const GROUP_COUNT: usize = 10;
+
+/// The delivery priority table of an event. Each of the ten delivery groups has a weight and
+/// groups with a weight of zero are not active for the event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EventPriority {
+    pub version_no: i32,
+    pub group_weights: [u8; GROUP_COUNT],
+}
+
+fn read_u16(buf: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        buf.get(pos..pos.checked_add(2)?)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(buf: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        buf.get(pos..pos.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+/// Position of `field` inside the flatbuffer table at `table`, if the field is present.
+/// Offsets that point before the start of the buffer or overflow give `None`.
+fn field_pos(buf: &[u8], table: usize, field: usize) -> Option<usize> {
+    // The vtable offset is signed and subtracted from the table position.
+    let offset = read_u32(buf, table)? as i32;
+    let vtable = if offset >= 0 {
+        table.checked_sub(offset as usize)?
+    } else {
+        table.checked_add(offset.unsigned_abs() as usize)?
+    };
+    let vtable_len = read_u16(buf, vtable)? as usize;
+    let entry = 4 + field * 2;
+    if entry + 2 > vtable_len {
+        return None;
+    }
+    match read_u16(buf, vtable.checked_add(entry)?)? {
+        0 => None,
+        offset => table.checked_add(offset as usize),
+    }
+}
+
+fn follow(buf: &[u8], pos: usize) -> Option<usize> {
+    pos.checked_add(read_u32(buf, pos)? as usize)
+}
+
+impl EventPriority {
+    /// Reads the `delivery_raid_priority_array` flatbuffer. Only the first entry is used since
+    /// the published files only ever contain one.
+    pub fn parse(buf: &[u8]) -> Option<Self> {
+        let root = follow(buf, 0)?;
+        let values = follow(buf, field_pos(buf, root, 0)?)?;
+        if read_u32(buf, values)? == 0 {
+            return None;
+        }
+        let priority = follow(buf, values.checked_add(4)?)?;
+
+        let version_no = match field_pos(buf, priority, 0) {
+            Some(pos) => read_u32(buf, pos)? as i32,
+            None => 0,
+        };
+
+        let mut group_weights = [0; GROUP_COUNT];
+        if let Some(pos) = field_pos(buf, priority, 1) {
+            let groups = follow(buf, pos)?;
+            for (i, weight) in group_weights.iter_mut().enumerate() {
+                if let Some(pos) = field_pos(buf, groups, i) {
+                    *weight = *buf.get(pos)?;
+                }
+            }
+        }
+
+        Some(Self {
+            version_no,
+            group_weights,
+        })
+    }
+
+    pub fn weight(&self, group_id: u8) -> u8 {
+        match group_id {
+            1..=10 => self.group_weights[group_id as usize - 1],
+            _ => 0,
+        }
+    }
+
+    pub fn is_active(&self, group_id: u8) -> bool {
+        self.weight(group_id) > 0
+    }
+
+    /// Chance in percent that an event crystal draws from `group_id`.
+    pub fn group_chance(&self, group_id: u8) -> f32 {
+        let total = self.group_weights.iter().map(|&w| w as u32).sum::<u32>();
+        if total == 0 {
+            0.0
+        } else {
+            self.weight(group_id) as f32 * 100.0 / total as f32
+        }
+    }
+}
+
+/// Chance for each version that an event crystal of the encounter's star level shows it: the
+/// chance of its delivery group being picked times its share of that group's star level.
+/// Without priority data every group is treated as equally likely.
+pub fn event_chances(
+    encounter: &RaidEncounter,
+    events: &[RaidEncounter],
+    priority: Option<&EventPriority>,
+) -> Vec<SpawnChance> {
+    let group_chance = match priority {
+        Some(priority) => priority.group_chance(encounter.delivery_group_id),
+        None => {
+            let mut groups = events
+                .iter()
+                .filter(|e| e.species != 0)
+                .map(|e| e.delivery_group_id)
+                .collect::<Vec<_>>();
+            groups.sort_unstable();
+            groups.dedup();
+            100.0 / groups.len().max(1) as f32
+        }
+    };
+
+    GameVersion::ALL
+        .into_iter()
+        .filter(|&version| available_in(encounter, version))
+        .map(|version| {
+            let total = events
+                .iter()
+                .filter(|e| {
+                    e.species != 0
+                        && e.delivery_group_id == encounter.delivery_group_id
+                        && e.difficulty == encounter.difficulty
+                        && available_in(e, version)
+                })
+                .map(|e| e.rate as u32)
+                .sum::<u32>();
+            let weight = encounter.rate as u32;
+            SpawnChance {
+                version,
+                weight,
+                chance: if total == 0 {
+                    0.0
+                } else {
+                    group_chance * weight as f32 / total as f32
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LATEST: &[u8] = include_bytes!("../raid_data/latest_priority");
+
+    #[test]
+    fn parses_published_priority() {
+        let priority = EventPriority::parse(LATEST).unwrap();
+        assert_eq!(priority.version_no, 20221202);
+        assert_eq!(priority.group_weights, [1, 5, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(priority.is_active(2));
+        assert!(!priority.is_active(3));
+        assert!((priority.group_chance(2) - 500.0 / 6.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn out_of_range_groups_have_no_weight() {
+        let priority = EventPriority::parse(LATEST).unwrap();
+        assert_eq!(priority.weight(0), 0);
+        assert_eq!(priority.weight(11), 0);
+        assert_eq!(priority.group_chance(11), 0.0);
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        assert_eq!(EventPriority::parse(&[]), None);
+        assert_eq!(EventPriority::parse(&LATEST[..40]), None);
+
+        // The root table's vtable offset points before the start of the buffer.
+        assert_eq!(EventPriority::parse(&[4, 0, 0, 0, 16, 0, 0, 0]), None);
+        // A negative offset pointing past the end of the buffer.
+        assert_eq!(EventPriority::parse(&[4, 0, 0, 0, 0, 0, 0, 0x80]), None);
+        // The root offset itself points past the end of the buffer.
+        assert_eq!(EventPriority::parse(&[0xFF, 0xFF, 0xFF, 0xFF]), None);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::i18n::tr;
+use crate::version::{version_chance, GameVersion, SpawnChance};
+use eframe::egui;
+use eframe::egui::Ui;
+
+/// How far the player is in the story, which decides the star levels normal crystals roll.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StoryProgress {
+    Start,
+    ThreeStars,
+    FourStars,
+    FiveStars,
+    SixStars,
+}
+
+impl StoryProgress {
+    pub const ALL: [StoryProgress; 5] = [
+        StoryProgress::Start,
+        StoryProgress::ThreeStars,
+        StoryProgress::FourStars,
+        StoryProgress::FiveStars,
+        StoryProgress::SixStars,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StoryProgress::Start => tr("Start (1-2★)"),
+            StoryProgress::ThreeStars => tr("3★ Unlocked"),
+            StoryProgress::FourStars => tr("4★ Unlocked"),
+            StoryProgress::FiveStars => tr("Post-game (5★)"),
+            StoryProgress::SixStars => tr("6★ Unlocked"),
+        }
+    }
+
+    /// Highest star level raids can have at this point, for normal and event crystals alike.
+    pub fn max_stars(self) -> u8 {
+        match self {
+            StoryProgress::Start => 2,
+            StoryProgress::ThreeStars => 3,
+            StoryProgress::FourStars => 4,
+            StoryProgress::FiveStars => 5,
+            StoryProgress::SixStars => 7,
+        }
+    }
+
+    pub fn unlocks(self, stars: u8) -> bool {
+        stars <= self.max_stars()
+    }
+
+    /// Chance in percent that a crystal of [`crystal_kind`] rolls `stars`. 6★ raids only come
+    /// from black crystals and 7★ raids from event crystals, so both are certain for their
+    /// crystal once unlocked and not comparable with the 1-5★ chances.
+    pub fn star_chance(self, stars: u8) -> f32 {
+        let table: [f32; 5] = match self {
+            StoryProgress::Start => [80.0, 20.0, 0.0, 0.0, 0.0],
+            StoryProgress::ThreeStars => [30.0, 40.0, 30.0, 0.0, 0.0],
+            StoryProgress::FourStars => [20.0, 20.0, 30.0, 30.0, 0.0],
+            StoryProgress::FiveStars => [0.0, 0.0, 40.0, 35.0, 25.0],
+            StoryProgress::SixStars => [0.0, 0.0, 30.0, 40.0, 30.0],
+        };
+        match stars {
+            1..=5 => table[stars as usize - 1],
+            6 | 7 if self == StoryProgress::SixStars => 100.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Chance in percent that a crystal shows this encounter: the star level chance times
+    /// the encounter's share of its star level.
+    pub fn appearance_chance(self, stars: u8, spawn: &SpawnChance) -> f32 {
+        self.star_chance(stars) * spawn.chance / 100.0
+    }
+}
+
+/// The crystal a star level's chances are relative to.
+pub fn crystal_kind(stars: u8) -> &'static str {
+    match stars {
+        6 => tr("black crystal"),
+        7 => tr("event crystal"),
+        _ => tr("crystal"),
+    }
+}
+
+/// The chance shown in the grid for a base game encounter at the given progress.
+pub fn grid_chance(
+    progress: StoryProgress,
+    stars: u8,
+    chances: &[SpawnChance],
+    version: Option<GameVersion>,
+) -> f32 {
+    progress.star_chance(stars) * version_chance(chances, version) / 100.0
+}
+
+pub fn progress_selector(ui: &mut Ui, progress: &mut StoryProgress) {
+    egui::ComboBox::from_id_source("story_progress")
+        .selected_text(progress.name())
+        .show_ui(ui, |ui| {
+            for p in StoryProgress::ALL {
+                ui.selectable_value(progress, p, p.name());
+            }
+        });
+}
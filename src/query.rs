@@ -0,0 +1,481 @@
+use crate::app::{SVRaidLookup, MAX_STARS};
+use crate::details_window::fixed_ability_index;
+use crate::filters::guaranteed_perfect_ivs;
+use crate::forms::species_matches;
+use crate::i18n::tr;
+use crate::names;
+use crate::rewards::Reward;
+use crate::types::GEM_TYPES;
+use crate::version::{available_in, GameVersion};
+use eframe::egui;
+use eframe::egui::{Color32, Ui, Widget};
+use sv_raid_reader::{
+    GemType, Gender, RaidEncounter, Seikaku, ShinyType, Tokusei, ABILITIES, ITEMS, MOVES, NATURES,
+    SPECIES, TYPES,
+};
+
+pub const KEYS: [&str; 11] = [
+    "species", "stars", "tera", "ability", "nature", "gender", "shiny", "ivs", "version", "move",
+    "drops",
+];
+
+const ABILITY_KEYWORDS: [&str; 2] = ["hidden", "random"];
+const GENDER_VALUES: [&str; 3] = ["random", "male", "female"];
+const SHINY_VALUES: [&str; 3] = ["random", "locked", "always"];
+const VERSION_VALUES: [&str; 2] = ["scarlet", "violet"];
+
+#[derive(Clone, PartialEq)]
+pub enum Term {
+    Species(String),
+    Stars(u8),
+    Tera(GemType),
+    HiddenAbility,
+    RandomAbility,
+    Ability(usize),
+    Nature(usize),
+    Gender(Gender),
+    Shiny(ShinyType),
+    PerfectIvs(u8),
+    Version(GameVersion),
+    Move(String),
+    Drops(String),
+}
+
+/// A parsed search such as `stars:6 tera:dragon drops:"ability patch"`.
+/// Bare words keep the old behaviour of matching species names.
+#[derive(Clone, Default, PartialEq)]
+pub struct Query {
+    pub terms: Vec<Term>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryError(pub String);
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        let terms = tokenize(input)
+            .into_iter()
+            .map(|token| match token.key {
+                Some(key) => parse_term(&key, &token.value),
+                None => parse_term("species", &token.value),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { terms })
+    }
+
+    /// The star level the query is restricted to, if any.
+    pub fn stars(&self) -> Option<u8> {
+        self.terms.iter().find_map(|term| match term {
+            Term::Stars(stars) => Some(*stars),
+            _ => None,
+        })
+    }
+
+    /// `species_text` is the encounter's [`crate::forms::search_text`], built by the caller.
+    pub fn matches(
+        &self,
+        encounter: &RaidEncounter,
+        species_text: &str,
+        rewards: impl FnOnce() -> Vec<Reward>,
+    ) -> bool {
+        let mut rewards = Some(rewards);
+        let mut reward_names = None;
+        self.terms.iter().all(|term| match term {
+            Term::Species(name) => species_text.contains(name.as_str()),
+            Term::Stars(stars) => encounter.difficulty == *stars,
+            Term::Tera(gem_type) => encounter.gem_type == *gem_type,
+            Term::HiddenAbility => encounter.tokusei == Tokusei::Set3,
+            Term::RandomAbility => {
+                matches!(encounter.tokusei, Tokusei::Random12 | Tokusei::Random123)
+            }
+            Term::Ability(ability) => fixed_ability_index(encounter) == Some(*ability),
+            Term::Nature(nature) => {
+                encounter.seikaku != Seikaku::Random && encounter.seikaku as usize - 1 == *nature
+            }
+            Term::Gender(gender) => encounter.gender == *gender,
+            Term::Shiny(shiny) => encounter.shiny == *shiny,
+            Term::PerfectIvs(ivs) => guaranteed_perfect_ivs(encounter) >= *ivs,
+            Term::Version(version) => available_in(encounter, *version),
+            Term::Move(name) => encounter.moves.iter().filter(|&&m| m != 0).any(|&m| {
+                let english = names::english(&MOVES, m as usize);
+                names::name_contains(english, names::move_name(m), name)
+            }),
+            Term::Drops(name) => {
+                let names = reward_names.get_or_insert_with(|| {
+                    rewards.take().map_or_else(Vec::new, |rewards| {
+                        rewards()
+                            .iter()
+                            .flat_map(|r| [r.name().to_lowercase(), r.local_name().to_lowercase()])
+                            .collect::<Vec<_>>()
+                    })
+                });
+                names.iter().any(|n| n.contains(name))
+            }
+        })
+    }
+}
+
+struct Token {
+    key: Option<String>,
+    value: String,
+}
+
+/// Splits on whitespace, keeping quoted values together.
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in input.chars().chain(std::iter::once(' ')) {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    let token = match current.split_once(':') {
+                        Some((key, value)) => Token {
+                            key: Some(key.to_lowercase()),
+                            value: value.to_string(),
+                        },
+                        None => Token {
+                            key: None,
+                            value: current.clone(),
+                        },
+                    };
+                    tokens.push(token);
+                    current.clear();
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    tokens
+}
+
+/// Finds a name in an English table or its translation in the selected language.
+fn find_exact(table: &[&str], localized: fn(usize) -> &'static str, value: &str) -> Option<usize> {
+    table.iter().enumerate().position(|(i, name)| {
+        name.eq_ignore_ascii_case(value) || localized(i).to_lowercase() == value
+    })
+}
+
+fn parse_term(key: &str, value: &str) -> Result<Term, QueryError> {
+    let value = value.trim().to_lowercase();
+    if value.is_empty() {
+        return Err(QueryError(format!(
+            "{} \"{}:\"",
+            tr("Missing value for"),
+            key
+        )));
+    }
+
+    let unknown = |what: &'static str| QueryError(format!("{} \"{}\"", tr(what), value));
+    match key {
+        "species" => {
+            if (1..SPECIES.len() as u16)
+                .flat_map(|species| (0..=5).map(move |form| (species, form)))
+                .any(|(species, form)| species_matches(species, form, &value))
+            {
+                Ok(Term::Species(value))
+            } else {
+                Err(unknown("Unknown species"))
+            }
+        }
+        "stars" => match value.parse::<u8>() {
+            Ok(stars @ 1..=MAX_STARS) => Ok(Term::Stars(stars)),
+            _ => Err(QueryError(format!(
+                "{} \"{}\"",
+                tr("Stars must be 1-7, got"),
+                value
+            ))),
+        },
+        "tera" => {
+            if value == "random" {
+                return Ok(Term::Tera(GemType::Random));
+            }
+            find_exact(&TYPES[..GEM_TYPES.len()], names::type_name, &value)
+                .map(|i| Term::Tera(GEM_TYPES[i]))
+                .ok_or_else(|| unknown("Unknown Tera type"))
+        }
+        "ability" => match value.as_str() {
+            "hidden" => Ok(Term::HiddenAbility),
+            "random" => Ok(Term::RandomAbility),
+            _ => find_exact(&ABILITIES[..], names::ability, &value)
+                .map(Term::Ability)
+                .ok_or_else(|| unknown("Unknown ability")),
+        },
+        "nature" => find_exact(&NATURES[..], names::nature, &value)
+            .map(Term::Nature)
+            .ok_or_else(|| unknown("Unknown nature")),
+        "gender" => match value.as_str() {
+            "random" => Ok(Term::Gender(Gender::Random)),
+            "male" | "m" => Ok(Term::Gender(Gender::Male)),
+            "female" | "f" => Ok(Term::Gender(Gender::Female)),
+            _ => Err(unknown("Unknown gender")),
+        },
+        "shiny" => match value.as_str() {
+            "random" => Ok(Term::Shiny(ShinyType::Random)),
+            "locked" | "never" | "no" => Ok(Term::Shiny(ShinyType::Never)),
+            "always" | "yes" => Ok(Term::Shiny(ShinyType::Always)),
+            _ => Err(unknown("Unknown shiny value")),
+        },
+        "ivs" => match value.parse::<u8>() {
+            Ok(ivs @ 0..=6) => Ok(Term::PerfectIvs(ivs)),
+            _ => Err(QueryError(format!(
+                "{} \"{}\"",
+                tr("IVs must be 0-6, got"),
+                value
+            ))),
+        },
+        "version" => match value.as_str() {
+            "scarlet" | "s" => Ok(Term::Version(GameVersion::Scarlet)),
+            "violet" | "v" => Ok(Term::Version(GameVersion::Violet)),
+            _ => Err(unknown("Unknown version")),
+        },
+        "move" => {
+            if MOVES
+                .iter()
+                .enumerate()
+                .any(|(i, m)| names::name_contains(m, names::move_name(i as u16), &value))
+            {
+                Ok(Term::Move(value))
+            } else {
+                Err(unknown("Unknown move"))
+            }
+        }
+        "drops" => {
+            if ITEMS
+                .iter()
+                .enumerate()
+                .any(|(i, item)| names::name_contains(item, names::item(i), &value))
+            {
+                Ok(Term::Drops(value))
+            } else {
+                Err(unknown("Unknown item"))
+            }
+        }
+        _ => Err(QueryError(format!(
+            "{} \"{}:\", {} {}",
+            tr("Unknown key"),
+            key,
+            tr("expected one of"),
+            KEYS.join(", ")
+        ))),
+    }
+}
+
+fn values_for(key: &str) -> Vec<&'static str> {
+    match key {
+        "species" => SPECIES.iter().skip(1).copied().collect(),
+        "stars" => vec!["1", "2", "3", "4", "5", "6", "7"],
+        "tera" => TYPES[..GEM_TYPES.len()].to_vec(),
+        "ability" => ABILITY_KEYWORDS
+            .iter()
+            .chain(ABILITIES.iter().skip(1))
+            .copied()
+            .collect(),
+        "nature" => NATURES.to_vec(),
+        "gender" => GENDER_VALUES.to_vec(),
+        "shiny" => SHINY_VALUES.to_vec(),
+        "ivs" => vec!["1", "2", "3", "4", "5", "6"],
+        "version" => VERSION_VALUES.to_vec(),
+        "move" => MOVES.iter().skip(1).copied().collect(),
+        "drops" => ITEMS.iter().copied().filter(|i| !i.is_empty()).collect(),
+        _ => vec![],
+    }
+}
+
+/// Suggestions for the word currently being typed, as `(label, completed input)` pairs.
+pub fn completions(input: &str, limit: usize) -> Vec<(String, String)> {
+    if input.ends_with(char::is_whitespace) || input.is_empty() {
+        return vec![];
+    }
+
+    // Byte offset just past the last whitespace, which may be wider than one byte such as the
+    // full-width space Japanese input methods type.
+    let word_start = |text: &str| {
+        text.char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8())
+    };
+    let quotes = input.matches('"').count();
+    let start = if quotes % 2 == 1 {
+        word_start(&input[..input.rfind('"').unwrap_or(0)])
+    } else {
+        word_start(input)
+    };
+    let (prefix, word) = input.split_at(start);
+
+    let quote = |value: &str| {
+        if value.contains(' ') {
+            format!("\"{}\"", value)
+        } else {
+            value.to_string()
+        }
+    };
+
+    match word.split_once(':') {
+        Some((key, partial)) => {
+            let key = key.to_lowercase();
+            let partial = partial.trim_matches('"').to_lowercase();
+            values_for(&key)
+                .into_iter()
+                .filter(|v| v.to_lowercase().starts_with(&partial) && v.len() > partial.len())
+                .take(limit)
+                .map(|v| (v.to_string(), format!("{}{}:{} ", prefix, key, quote(v))))
+                .collect()
+        }
+        None => {
+            let partial = word.to_lowercase();
+            KEYS.iter()
+                .filter(|k| k.starts_with(&partial))
+                .map(|k| (format!("{}:", k), format!("{}{}:", prefix, k)))
+                .take(limit)
+                .collect()
+        }
+    }
+}
+
+/// The species search box shared by the side panel and mobile bar, with completions and errors.
+pub fn query_input(app: &mut SVRaidLookup, ui: &mut Ui) {
+    let response = egui::TextEdit::singleline(&mut app.species_filter)
+        .hint_text(tr("e.g. stars:6 tera:dragon"))
+        .ui(ui);
+    if response.changed() {
+        app.update_query();
+    }
+
+    let suggestions = completions(&app.species_filter, 6);
+    if !suggestions.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            for (label, completed) in suggestions {
+                if ui.small_button(label).clicked() {
+                    app.species_filter = completed;
+                    app.update_query();
+                    response.request_focus();
+                }
+            }
+        });
+    }
+
+    if let Some(error) = app.query_error.as_ref() {
+        ui.colored_label(Color32::RED, error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(input: &str) -> Vec<(Option<String>, String)> {
+        tokenize(input)
+            .into_iter()
+            .map(|token| (token.key, token.value))
+            .collect()
+    }
+
+    fn error(input: &str) -> String {
+        match Query::parse(input) {
+            Ok(_) => panic!("\"{}\" should not parse", input),
+            Err(error) => error.0,
+        }
+    }
+
+    #[test]
+    fn tokenize_keeps_quoted_values_together() {
+        assert_eq!(
+            tokens(r#"Dragonite  STARS:6 drops:"ability patch""#),
+            vec![
+                (None, "Dragonite".to_string()),
+                (Some("stars".to_string()), "6".to_string()),
+                (Some("drops".to_string()), "ability patch".to_string()),
+            ]
+        );
+        assert!(tokens("   ").is_empty());
+    }
+
+    #[test]
+    fn parses_terms() {
+        let query =
+            Query::parse("stars:6 tera:dragon gender:f shiny:locked ivs:6 version:v").unwrap();
+        assert!(matches!(
+            query.terms.as_slice(),
+            [
+                Term::Stars(6),
+                Term::Tera(GemType::Dragon),
+                Term::Gender(Gender::Female),
+                Term::Shiny(ShinyType::Never),
+                Term::PerfectIvs(6),
+                Term::Version(GameVersion::Violet),
+            ]
+        ));
+        assert_eq!(query.stars(), Some(6));
+        assert!(Query::parse("").unwrap().terms.is_empty());
+    }
+
+    #[test]
+    fn parses_names() {
+        let query = Query::parse("dragonite ability:hidden nature:ADAMANT tera:random").unwrap();
+        assert!(matches!(
+            query.terms.as_slice(),
+            [
+                Term::Species(species),
+                Term::HiddenAbility,
+                Term::Nature(nature),
+                Term::Tera(GemType::Random),
+            ] if species == "dragonite" && NATURES[*nature] == "Adamant"
+        ));
+        assert!(matches!(
+            Query::parse(r#"ability:random move:"dragon dance""#)
+                .unwrap()
+                .terms
+                .as_slice(),
+            [Term::RandomAbility, Term::Move(name)] if name == "dragon dance"
+        ));
+    }
+
+    #[test]
+    fn reports_invalid_terms() {
+        assert_eq!(error("stars:8"), "Stars must be 1-7, got \"8\"");
+        assert_eq!(error("stars:"), "Missing value for \"stars:\"");
+        assert_eq!(error("ivs:seven"), "IVs must be 0-6, got \"seven\"");
+        assert_eq!(error("tera:plastic"), "Unknown Tera type \"plastic\"");
+        assert_eq!(error("gender:x"), "Unknown gender \"x\"");
+        assert_eq!(error("qqqq"), "Unknown species \"qqqq\"");
+        assert!(error("color:red").starts_with("Unknown key \"color:\""));
+    }
+
+    #[test]
+    fn completes_keys_and_values() {
+        assert!(completions("", 5).is_empty());
+        assert!(completions("stars:6 ", 5).is_empty());
+        assert_eq!(
+            completions("stars:6 te", 5),
+            vec![("tera:".to_string(), "stars:6 tera:".to_string())]
+        );
+        assert_eq!(
+            completions("tera:dra", 5),
+            vec![("Dragon".to_string(), "tera:Dragon ".to_string())]
+        );
+        // Values with spaces are quoted, also while the quote is still open.
+        assert!(completions(r#"drops:"ability pa"#, 10).contains(&(
+            "Ability Patch".to_string(),
+            r#"drops:"Ability Patch" "#.to_string()
+        )));
+        // A complete value has nothing left to suggest.
+        assert!(completions("stars:6", 5).is_empty());
+        assert_eq!(completions("s", 2).len(), 2);
+        // Multibyte spaces separate words too.
+        assert_eq!(
+            completions("a\u{3000}tera:dra", 5),
+            vec![("Dragon".to_string(), "a\u{3000}tera:Dragon ".to_string())]
+        );
+        assert_eq!(
+            completions("a\u{a0}te", 5),
+            vec![("tera:".to_string(), "a\u{a0}tera:".to_string())]
+        );
+        // Used to panic by splitting inside the space.
+        completions("a\u{3000}b", 5);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::names;
+use sv_raid_reader::{ItemTable, RaidEncounter, ITEMS};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Reward {
+    pub item_id: u16,
+    pub amount: u8,
+    /// Drop chance in percent. Fixed rewards are always 100.
+    pub probability: f32,
+    pub fixed: bool,
+}
+
+impl Reward {
+    pub fn name(&self) -> &'static str {
+        names::english(&ITEMS, self.item_id as usize)
+    }
+
+    /// The item name in the selected language.
+    pub fn local_name(&self) -> &'static str {
+        names::item(self.item_id as usize)
+    }
+
+    pub fn label(&self) -> String {
+        if self.fixed {
+            format!("{}× {}", self.amount, self.local_name())
+        } else {
+            format!(
+                "{}× {} ({:.1}%)",
+                self.amount,
+                self.local_name(),
+                self.probability
+            )
+        }
+    }
+}
+
+/// Collects the fixed and lottery rewards an encounter points at in the given tables.
+pub fn encounter_rewards(
+    encounter: &RaidEncounter,
+    fixed: &ItemTable,
+    lottery: &ItemTable,
+) -> Vec<Reward> {
+    let fixed_rewards = fixed
+        .0
+        .get(&encounter.drop_table_fix)
+        .into_iter()
+        .flatten()
+        .map(|item| Reward {
+            item_id: item.item_id,
+            amount: item.amount,
+            probability: 100.0,
+            fixed: true,
+        });
+
+    let lottery_rewards = lottery
+        .0
+        .get(&encounter.drop_table_random)
+        .into_iter()
+        .flatten()
+        .map(|item| Reward {
+            item_id: item.item_id,
+            amount: item.amount,
+            probability: item.probability,
+            fixed: false,
+        });
+
+    fixed_rewards
+        .chain(lottery_rewards)
+        .filter(|r| r.item_id != 0 && (r.item_id as usize) < ITEMS.len())
+        .collect()
+}
@@ -0,0 +1,210 @@
+use crate::encounter_details::{AbilitySpec, EncounterDetails, IvSpec};
+use sv_raid_reader::{personal_table, PersonalInfo, ShinyType};
+
+/// Raids generate their Pokémon from the crystal seed with Xoroshiro128+.
+struct Xoroshiro128Plus {
+    s0: u64,
+    s1: u64,
+}
+
+impl Xoroshiro128Plus {
+    fn new(seed: u64) -> Self {
+        Self {
+            s0: seed,
+            s1: 0x82A2B175229D6A5B,
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        let result = self.s0.wrapping_add(self.s1);
+        self.s1 ^= self.s0;
+        self.s0 = self.s0.rotate_left(24) ^ self.s1 ^ (self.s1 << 16);
+        self.s1 = self.s1.rotate_left(37);
+        result
+    }
+
+    /// Random value below `max`, drawn by masking and rerolling like the game does.
+    fn next_int(&mut self, max: u64) -> u64 {
+        let mask = u64::MAX >> (max - 1).leading_zeros();
+        loop {
+            let value = self.next() & mask;
+            if value < max {
+                return value;
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Shininess {
+    None,
+    Star,
+    Square,
+}
+
+/// The part of a raid Pokémon that is rolled before gender. Gender, nature and size come later
+/// in the sequence and need the species' gender ratio, so they are not predicted here.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SeedResult {
+    pub encryption_constant: u32,
+    /// The PID as rolled against the fake trainer ID, see `roll_pid`.
+    pub pid: u32,
+    pub shiny: Shininess,
+    /// HP, Atk, Def, SpA, SpD, Spe.
+    pub ivs: [u8; 6],
+    /// Ability slot, 0-1 for the regular abilities and 2 for the hidden one.
+    pub ability_slot: usize,
+    pub ability: Option<usize>,
+}
+
+fn shiny_xor(pid: u32, tid: u32) -> u32 {
+    (pid >> 16) ^ (pid & 0xFFFF) ^ (tid >> 16) ^ (tid & 0xFFFF)
+}
+
+/// Parses a seed typed as hex, with or without a `0x` prefix.
+pub fn parse_seed(text: &str) -> Option<u32> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    u32::from_str_radix(text, 16).ok()
+}
+
+/// Rolls the EC and PID, the first values drawn from the seed. Shininess is decided against a
+/// fake trainer ID rolled from the seed as well, not the player's ID. The game afterwards
+/// rewrites the PID so the result holds for whoever catches it, so the PID returned here is
+/// the one before that rewrite and won't match the caught Pokémon's unless the IDs agree.
+fn roll_pid(rng: &mut Xoroshiro128Plus, shiny: ShinyType) -> (u32, u32, Shininess) {
+    let encryption_constant = rng.next_int(u32::MAX as u64) as u32;
+    let tid = rng.next_int(u32::MAX as u64) as u32;
+    let mut pid = rng.next_int(u32::MAX as u64) as u32;
+
+    let xor = shiny_xor(pid, tid);
+    let shiny = match shiny {
+        ShinyType::Never => {
+            if xor < 16 {
+                pid ^= 0x1000_0000;
+            }
+            Shininess::None
+        }
+        // Always-shiny raids rewrite the upper half of the PID into a square shiny one.
+        ShinyType::Always if xor >= 16 => {
+            pid = (((tid >> 16) ^ (tid & 0xFFFF) ^ (pid & 0xFFFF)) << 16) | (pid & 0xFFFF);
+            Shininess::Square
+        }
+        _ if xor == 0 => Shininess::Square,
+        _ if xor < 16 => Shininess::Star,
+        _ => Shininess::None,
+    };
+    (encryption_constant, pid, shiny)
+}
+
+/// Rolls IVs after the PID: fixed ones are kept, then the guaranteed perfect stats are picked
+/// at random and the rest are rolled in order.
+fn roll_ivs(rng: &mut Xoroshiro128Plus, spec: IvSpec) -> [u8; 6] {
+    let mut ivs = [None; 6];
+    match spec {
+        IvSpec::Fixed(fixed) => ivs = fixed.map(Some),
+        IvSpec::Perfect(count) => {
+            for _ in 0..count.min(6) {
+                loop {
+                    let index = rng.next_int(6) as usize;
+                    if ivs[index].is_none() {
+                        ivs[index] = Some(31);
+                        break;
+                    }
+                }
+            }
+        }
+        IvSpec::Random => {}
+    }
+    ivs.map(|iv| iv.unwrap_or_else(|| rng.next_int(32) as u8))
+}
+
+/// Rolls the encounter's EC, PID, shininess, IVs and ability from a raid seed.
+pub fn generate(details: &EncounterDetails, seed: u32) -> SeedResult {
+    let mut rng = Xoroshiro128Plus::new(seed as u64);
+    let (encryption_constant, pid, shiny) = roll_pid(&mut rng, details.shiny);
+    let ivs = roll_ivs(&mut rng, details.ivs);
+
+    let ability_slot = match details.ability {
+        AbilitySpec::Random12 => rng.next_int(2) as usize,
+        AbilitySpec::Random123 => rng.next_int(3) as usize,
+        AbilitySpec::Fixed { slot, .. } => slot,
+    };
+    let ability = personal_table::SV
+        .get_form_entry(details.species as usize, details.form as usize)
+        .get_ability_index(ability_slot);
+
+    SeedResult {
+        encryption_constant,
+        pid,
+        shiny,
+        ivs,
+        ability_slot,
+        ability,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xoroshiro_matches_reference_sequence() {
+        let mut rng = Xoroshiro128Plus::new(0);
+        assert_eq!(rng.next(), 0x82A2B175229D6A5B);
+        assert_eq!(rng.next(), 0x8784DF589D1C98FF);
+        assert_eq!(rng.next(), 0xE2B2A24E12B7A66F);
+    }
+
+    #[test]
+    fn rolls_ec_and_pid() {
+        let mut rng = Xoroshiro128Plus::new(0);
+        assert_eq!(
+            roll_pid(&mut rng, ShinyType::Random),
+            (0x229D6A5B, 0x12B7A66F, Shininess::None)
+        );
+        let mut rng = Xoroshiro128Plus::new(0x12345678);
+        assert_eq!(
+            roll_pid(&mut rng, ShinyType::Random),
+            (0x34D1C0D3, 0xEDB11674, Shininess::None)
+        );
+    }
+
+    #[test]
+    fn always_shiny_rewrites_pid() {
+        let mut rng = Xoroshiro128Plus::new(0);
+        let (_, pid, shiny) = roll_pid(&mut rng, ShinyType::Always);
+        assert_eq!(pid, 0xA38CA66F);
+        assert_eq!(shiny, Shininess::Square);
+        assert_eq!(shiny_xor(pid, 0x9D1C98FF), 0);
+    }
+
+    #[test]
+    fn rolls_ivs() {
+        let mut rng = Xoroshiro128Plus::new(0);
+        roll_pid(&mut rng, ShinyType::Random);
+        assert_eq!(roll_ivs(&mut rng, IvSpec::Random), [14, 23, 26, 10, 4, 25]);
+
+        let mut rng = Xoroshiro128Plus::new(0);
+        roll_pid(&mut rng, ShinyType::Random);
+        assert_eq!(
+            roll_ivs(&mut rng, IvSpec::Perfect(3)),
+            [5, 31, 31, 31, 31, 18]
+        );
+
+        let fixed = [31, 0, 31, 31, 31, 0];
+        assert_eq!(roll_ivs(&mut rng, IvSpec::Fixed(fixed)), fixed);
+    }
+
+    #[test]
+    fn parses_hex_seeds() {
+        assert_eq!(parse_seed("0x1A2B3C4D"), Some(0x1A2B3C4D));
+        assert_eq!(parse_seed(" 1a2b3c4d "), Some(0x1A2B3C4D));
+        assert_eq!(parse_seed("0X0"), Some(0));
+        assert_eq!(parse_seed("123456789"), None);
+        assert_eq!(parse_seed("seed"), None);
+    }
+}
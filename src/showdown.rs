@@ -0,0 +1,249 @@
+use crate::encounter_details::{EncounterDetails, IvSpec};
+use crate::forms::form_suffix;
+use crate::names;
+use crate::types::GEM_TYPES;
+use sv_raid_reader::{Gender, ABILITIES, MOVES, NATURES, SPECIES, TYPES};
+
+const STAT_NAMES: [&str; 6] = ["HP", "Atk", "Def", "SpA", "SpD", "Spe"];
+
+pub fn showdown_species(species: u16, form: u8) -> String {
+    match form_suffix(species, form) {
+        Some(suffix) => format!("{}-{}", names::english(&SPECIES, species as usize), suffix),
+        None => names::english(&SPECIES, species as usize).to_string(),
+    }
+}
+
+/// Builds a Showdown importable set for a raid boss. Only the fields the encounter
+/// actually fixes are written so Showdown falls back to its defaults for the rest.
+pub fn showdown_set(details: &EncounterDetails) -> String {
+    let mut lines = Vec::new();
+
+    let gender = match details.gender {
+        Gender::Male => " (M)",
+        Gender::Female => " (F)",
+        Gender::Random => "",
+    };
+    lines.push(format!(
+        "{}{}",
+        showdown_species(details.species, details.form),
+        gender
+    ));
+
+    if let Some(ability) = details.fixed_ability() {
+        lines.push(format!("Ability: {}", names::english(&ABILITIES, ability)));
+    }
+    lines.push(format!("Level: {}", details.level));
+    if details.is_always_shiny() {
+        lines.push("Shiny: Yes".to_string());
+    }
+    if let Some(tera_type) = details.tera_type {
+        lines.push(format!("Tera Type: {}", names::english(&TYPES, tera_type)));
+    }
+
+    if let IvSpec::Fixed(ivs) = details.ivs {
+        let ivs = ivs
+            .iter()
+            .zip(STAT_NAMES)
+            .filter(|(iv, _)| **iv != 31)
+            .map(|(iv, stat)| format!("{} {}", iv, stat))
+            .collect::<Vec<_>>();
+        if !ivs.is_empty() {
+            lines.push(format!("IVs: {}", ivs.join(" / ")));
+        }
+    }
+
+    if let Some(nature) = details.nature {
+        lines.push(format!("{} Nature", names::english(&NATURES, nature)));
+    }
+
+    for &move_id in details.moves.iter() {
+        lines.push(format!("- {}", names::english(&MOVES, move_id as usize)));
+    }
+
+    lines.join("\n")
+}
+
+/// A set read back from a Showdown export. Missing fields use Showdown's defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowdownSet {
+    pub species: u16,
+    pub form: u8,
+    pub item: String,
+    pub ability: String,
+    pub level: u8,
+    pub tera_type: Option<usize>,
+    pub nature: usize,
+    pub evs: [u16; 6],
+    pub ivs: [u8; 6],
+    pub moves: Vec<String>,
+}
+
+/// Finds a species and form from a Showdown species name such as `Tauros-Paldea-Blaze`.
+pub fn find_species(name: &str) -> Option<(u16, u8)> {
+    let name = name.trim();
+    if let Some(species) = SPECIES
+        .iter()
+        .position(|s| !s.is_empty() && s.eq_ignore_ascii_case(name))
+    {
+        return Some((species as u16, 0));
+    }
+    (1..SPECIES.len() as u16)
+        .flat_map(|species| (1..=5).map(move |form| (species, form)))
+        .find(|&(species, form)| {
+            form_suffix(species, form).is_some()
+                && showdown_species(species, form).eq_ignore_ascii_case(name)
+        })
+}
+
+fn parse_spread<T: std::str::FromStr + Copy>(line: &str, spread: &mut [T; 6]) {
+    for part in line.split('/') {
+        let mut words = part.split_whitespace();
+        if let (Some(value), Some(stat)) = (words.next(), words.next()) {
+            if let (Ok(value), Some(i)) = (
+                value.parse::<T>(),
+                STAT_NAMES.iter().position(|s| s.eq_ignore_ascii_case(stat)),
+            ) {
+                spread[i] = value;
+            }
+        }
+    }
+}
+
+pub fn parse_showdown_set(text: &str) -> Result<ShowdownSet, String> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    let first = lines.next().ok_or_else(|| "Empty set".to_string())?;
+
+    let (name, item) = match first.split_once(" @ ") {
+        Some((name, item)) => (name, item.trim().to_string()),
+        None => (first, String::new()),
+    };
+    let name = name
+        .trim()
+        .trim_end_matches("(M)")
+        .trim_end_matches("(F)")
+        .trim();
+    // "Nickname (Species)" puts the species in the last parentheses.
+    let species_name = match (name.rfind('('), name.rfind(')')) {
+        (Some(start), Some(end)) if start < end => &name[start + 1..end],
+        _ => name,
+    };
+    let (species, form) = find_species(species_name)
+        .ok_or_else(|| format!("Unknown species \"{}\"", species_name))?;
+
+    let mut set = ShowdownSet {
+        species,
+        form,
+        item,
+        ability: String::new(),
+        level: 100,
+        tera_type: None,
+        nature: 0,
+        evs: [0; 6],
+        ivs: [31; 6],
+        moves: vec![],
+    };
+
+    for line in lines {
+        if let Some(ability) = line.strip_prefix("Ability:") {
+            set.ability = ability.trim().to_string();
+        } else if let Some(level) = line.strip_prefix("Level:") {
+            set.level = level.trim().parse().unwrap_or(100);
+        } else if let Some(tera) = line.strip_prefix("Tera Type:") {
+            set.tera_type = TYPES
+                .iter()
+                .take(GEM_TYPES.len())
+                .position(|t| t.eq_ignore_ascii_case(tera.trim()));
+        } else if let Some(evs) = line.strip_prefix("EVs:") {
+            parse_spread(evs, &mut set.evs);
+        } else if let Some(ivs) = line.strip_prefix("IVs:") {
+            parse_spread(ivs, &mut set.ivs);
+        } else if let Some(nature) = line.strip_suffix(" Nature") {
+            if let Some(i) = NATURES.iter().position(|n| n.eq_ignore_ascii_case(nature)) {
+                set.nature = i;
+            }
+        } else if let Some(move_name) = line.strip_prefix('-') {
+            set.moves.push(move_name.trim().to_string());
+        }
+    }
+
+    Ok(set)
+}
+
+/// Parses a whole team, where sets are separated by blank lines.
+pub fn parse_showdown_team(text: &str) -> Vec<Result<ShowdownSet, String>> {
+    let mut sets = Vec::new();
+    let mut current = String::new();
+    for line in text.lines().chain(std::iter::once("")) {
+        if line.trim().is_empty() {
+            if !current.trim().is_empty() {
+                sets.push(parse_showdown_set(&current));
+            }
+            current.clear();
+        } else {
+            current.push_str(line);
+            current.push('\n');
+        }
+    }
+    sets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_species_and_forms() {
+        assert_eq!(find_species("Garchomp"), Some((445, 0)));
+        assert_eq!(find_species(" garchomp "), Some((445, 0)));
+        assert_eq!(find_species("Tauros-Paldea-Aqua"), Some((128, 3)));
+        assert_eq!(find_species("Rotom-Wash"), Some((479, 2)));
+        assert_eq!(find_species("Not a Pokemon"), None);
+    }
+
+    #[test]
+    fn parses_nicknamed_gendered_sets() {
+        let set = parse_showdown_set(
+            "Chompy (Garchomp) (M) @ Choice Band
+             Ability: Rough Skin
+             Level: 50
+             Tera Type: Ground
+             EVs: 4 HP / 252 Atk / 252 Spe
+             Jolly Nature
+             IVs: 0 SpA
+             - Earthquake
+             - Outrage",
+        )
+        .unwrap();
+        assert_eq!((set.species, set.form), (445, 0));
+        assert_eq!(set.item, "Choice Band");
+        assert_eq!(set.ability, "Rough Skin");
+        assert_eq!(set.level, 50);
+        assert_eq!(set.tera_type, Some(4));
+        assert_eq!(set.evs, [4, 252, 0, 0, 0, 252]);
+        assert_eq!(set.ivs, [31, 31, 31, 0, 31, 31]);
+        assert_eq!(NATURES[set.nature], "Jolly");
+        assert_eq!(set.moves, ["Earthquake", "Outrage"]);
+    }
+
+    #[test]
+    fn parses_alternate_forms() {
+        let set = parse_showdown_set("Tauros-Paldea-Aqua (F)\n- Wave Crash").unwrap();
+        assert_eq!((set.species, set.form), (128, 3));
+        assert_eq!(set.item, "");
+        assert_eq!(set.level, 100);
+        assert_eq!(set.moves, ["Wave Crash"]);
+
+        let set = parse_showdown_set("Splash (Rotom-Wash) @ Leftovers").unwrap();
+        assert_eq!((set.species, set.form), (479, 2));
+        assert_eq!(set.item, "Leftovers");
+    }
+
+    #[test]
+    fn rejects_unknown_species() {
+        assert_eq!(
+            parse_showdown_set("Missingno @ Leftovers"),
+            Err("Unknown species \"Missingno\"".to_string())
+        );
+        assert!(parse_showdown_set("\n  \n").is_err());
+    }
+}
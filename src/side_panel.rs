@@ -1,46 +1,59 @@
-use crate::app::SVRaidLookup;
+use crate::app::{SVRaidLookup, MAX_STARS};
 use crate::encounter_grid::encounter_grid;
+use crate::event_data::errors_ui;
+use crate::i18n::tr;
+use crate::names::language_selector;
+use crate::progress::progress_selector;
+use crate::query::query_input;
+use crate::version::version_selector;
 use eframe::egui;
 use eframe::egui::{Context, Widget};
-use sv_raid_reader::{
-    DIFFICULTY_01, DIFFICULTY_02, DIFFICULTY_03, DIFFICULTY_04, DIFFICULTY_05, DIFFICULTY_06,
-    SPECIES,
-};
 
 pub fn draw_side_panel(app: &mut SVRaidLookup, ctx: &Context) {
     egui::SidePanel::left("left_panel").show(ctx, |ui| {
         egui::Grid::new("filters").num_columns(2).show(ui, |ui| {
-            ui.label("Stars:");
+            ui.label(tr("Stars:"));
             ui.vertical_centered_justified(|ui| {
                 if egui::DragValue::new(&mut app.star_level)
-                    .clamp_range(1..=6)
+                    .clamp_range(1..=MAX_STARS)
                     .ui(ui)
                     .changed()
                 {
-                    app.encounters = match app.star_level {
-                        2 => DIFFICULTY_02.to_vec(),
-                        3 => DIFFICULTY_03.to_vec(),
-                        4 => DIFFICULTY_04.to_vec(),
-                        5 => DIFFICULTY_05.to_vec(),
-                        6 => DIFFICULTY_06.to_vec(),
-                        _ => DIFFICULTY_01.to_vec(),
-                    };
-                    app.encounters.sort_by_key(|e| SPECIES[e.species as usize]);
+                    app.set_star_level(app.star_level);
                 };
             });
             ui.end_row();
-            ui.label("Species:");
+            ui.label("");
+            if ui.checkbox(&mut app.all_stars, tr("All stars")).changed() {
+                app.refresh_encounters();
+            }
+            ui.end_row();
+            ui.label(tr("Version:"));
+            ui.horizontal(|ui| version_selector(ui, &mut app.game_version));
+            ui.end_row();
+            ui.label(tr("Progress:"));
+            progress_selector(ui, &mut app.progress);
+            ui.end_row();
+            ui.label(tr("Language:"));
+            if language_selector(ui) {
+                app.language_changed(ctx);
+            }
+            ui.end_row();
+            ui.label(tr("Species:"));
             ui.vertical_centered_justified(|ui| {
-                egui::TextEdit::singleline(&mut app.species_filter).ui(ui);
+                query_input(app, ui);
             });
         });
+        ui.add_space(5.0);
+        egui::CollapsingHeader::new(tr("Filters")).show(ui, |ui| {
+            app.filter.ui(ui, &mut app.game_version);
+        });
         ui.add_space(15.0);
-        ui.vertical_centered_justified(|ui| if ui.button("Item Farming Raid").clicked() {});
+        ui.vertical_centered_justified(|ui| if ui.button(tr("Item Farming Raid")).clicked() {});
+        errors_ui(ui, &app.load_errors);
 
         ui.add_space(15.0);
 
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            encounter_grid(app, ui, ctx);
-        });
+        encounter_grid(app, ui, ctx, f32::INFINITY);
     });
 }
@@ -0,0 +1,192 @@
+use crate::app::{EncounterSource, SVRaidLookup};
+use crate::encounter_details::EncounterDetails;
+use crate::forms::display_name;
+use crate::i18n::tr;
+use crate::names;
+use crate::rewards::Reward;
+use eframe::egui;
+use eframe::egui::{RichText, Ui};
+use sv_raid_reader::{personal_table, TYPES};
+
+/// One raid column on the species page.
+pub struct SpeciesRaid {
+    pub source: String,
+    pub stars: String,
+    pub tera_type: String,
+    pub ability: String,
+    pub nature: String,
+    pub hp: String,
+    pub moves: Vec<String>,
+    pub rewards: Vec<String>,
+}
+
+pub struct SpeciesPage {
+    pub title: String,
+    pub types: String,
+    pub abilities: String,
+    pub gender_ratio: String,
+    pub base_stat_total: String,
+    pub raids: Vec<SpeciesRaid>,
+}
+
+impl SpeciesPage {
+    pub fn new(app: &SVRaidLookup, species: u16, form: u8) -> Self {
+        let personal = personal_table::SV.get_form_entry(species as usize, form as usize);
+
+        let type_1 = personal.get_type_1();
+        let type_2 = personal.get_type_2();
+        let types = if type_1 != type_2 && type_2 < TYPES.len() {
+            format!(
+                "{} {}/{}",
+                tr("Type:"),
+                names::type_name(type_1),
+                names::type_name(type_2)
+            )
+        } else {
+            format!("{} {}", tr("Type:"), names::type_name(type_1))
+        };
+
+        let ability_slot = |slot| {
+            personal
+                .get_ability_index(slot)
+                .map(names::ability)
+                .unwrap_or("-")
+        };
+        let abilities = format!(
+            "{} {} / {} / {} (H)",
+            tr("Abilities:"),
+            ability_slot(0),
+            ability_slot(1),
+            ability_slot(2)
+        );
+
+        let gender_ratio = match personal.get_gender() {
+            255 => tr("Genderless").to_string(),
+            254 => format!("100% {}", tr("Female")),
+            0 => format!("100% {}", tr("Male")),
+            // Stored as 256ths minus one, so 31, 63, 127 and 191 are 1/8, 1/4, 1/2 and 3/4
+            // female.
+            ratio => {
+                let female = (ratio as f32 + 1.0) * 100.0 / 256.0;
+                format!(
+                    "{:.1}% {} / {:.1}% {}",
+                    100.0 - female,
+                    tr("Male"),
+                    female,
+                    tr("Female")
+                )
+            }
+        };
+        let gender_ratio = format!("{} {}", tr("Gender:"), gender_ratio);
+
+        let base_stat_total = format!(
+            "{} {}",
+            tr("Base Stat Total:"),
+            personal.stats().iter().map(|&s| s as u32).sum::<u32>()
+        );
+
+        let mut encounters = app
+            .all_encounters()
+            .into_iter()
+            .filter(|(_, e)| e.species == species && e.form == form)
+            .collect::<Vec<_>>();
+        encounters.sort_by_key(|(source, e)| {
+            (
+                e.difficulty,
+                matches!(source, EncounterSource::Event { .. }),
+            )
+        });
+
+        let raids = encounters
+            .into_iter()
+            .map(|(source, encounter)| {
+                let details = EncounterDetails::new(&encounter, app.rewards(source, &encounter));
+                SpeciesRaid {
+                    source: match source {
+                        EncounterSource::Base { .. } => tr("Base Game").to_string(),
+                        EncounterSource::Event { .. } => tr("Event").to_string(),
+                    },
+                    stars: format!("{}★", details.stars),
+                    tera_type: details.tera_label().to_string(),
+                    ability: details.ability_label().to_string(),
+                    nature: details.nature_label().to_string(),
+                    hp: format!("{}x", details.hp_coef),
+                    moves: details
+                        .moves
+                        .iter()
+                        .map(|&m| names::move_name(m).to_string())
+                        .collect(),
+                    rewards: details.rewards.iter().map(Reward::label).collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            title: display_name(species, form),
+            types,
+            abilities,
+            gender_ratio,
+            base_stat_total,
+            raids,
+        }
+    }
+
+    /// Draws the page and returns true when the user asked to close it.
+    pub fn show(&self, ui: &mut Ui) -> bool {
+        let close = ui.button(format!("⏴ {}", tr("Back"))).clicked();
+        ui.heading(&self.title);
+        ui.label(&self.types);
+        ui.label(&self.abilities);
+        ui.label(&self.gender_ratio);
+        ui.label(&self.base_stat_total);
+        ui.add_space(5.0);
+        ui.separator();
+        ui.add_space(5.0);
+
+        if self.raids.is_empty() {
+            ui.label(tr("No raids found for this species."));
+            return close;
+        }
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("species_raids")
+                .striped(true)
+                .min_col_width(120.0)
+                .show(ui, |ui| {
+                    ui.label("");
+                    for raid in self.raids.iter() {
+                        ui.label(RichText::new(format!("{} {}", raid.stars, raid.source)).strong());
+                    }
+                    ui.end_row();
+
+                    let rows: [(&str, fn(&SpeciesRaid) -> String); 4] = [
+                        ("Tera Type", |r| r.tera_type.clone()),
+                        ("Ability", |r| r.ability.clone()),
+                        ("Nature", |r| r.nature.clone()),
+                        ("HP", |r| r.hp.clone()),
+                    ];
+                    for (name, value) in rows {
+                        ui.label(tr(name));
+                        for raid in self.raids.iter() {
+                            ui.label(value(raid));
+                        }
+                        ui.end_row();
+                    }
+
+                    ui.label(tr("Moves"));
+                    for raid in self.raids.iter() {
+                        ui.label(raid.moves.join("\n"));
+                    }
+                    ui.end_row();
+
+                    ui.label(tr("Rewards"));
+                    for raid in self.raids.iter() {
+                        ui.label(raid.rewards.join("\n"));
+                    }
+                    ui.end_row();
+                });
+        });
+
+        close
+    }
+}
@@ -0,0 +1,282 @@
+use crate::forms::form_suffix;
+use crate::names;
+use crate::types::TYPE_COLORS;
+use eframe::egui::{pos2, Color32, Context, Rect, Response, Shape, Stroke, Ui, Vec2};
+use egui_extras::RetainedImage;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use sv_raid_reader::{RaidEncounter, ShinyType, SPECIES};
+
+const SPRITE_URL: &str =
+    "https://raw.githubusercontent.com/Lincoln-LM/sv-live-map/master/resources/sprites/";
+
+/// Shiny sprites by national dex number. Only used for base forms since the repository numbers
+/// alternate forms differently.
+const SHINY_SPRITE_URL: &str =
+    "https://raw.githubusercontent.com/PokeAPI/sprites/master/sprites/pokemon/shiny/";
+
+/// Shiny sprites named like Showdown's species IDs, used for alternate forms.
+const SHINY_FORM_SPRITE_URL: &str = "https://play.pokemonshowdown.com/sprites/gen5-shiny/";
+
+/// Folder bundled sprites are shipped in. Trunk copies it next to the web build, and native
+/// builds look for it next to the executable.
+#[cfg(not(target_arch = "wasm32"))]
+const BUNDLED_DIR: &str = "assets/sprites";
+
+/// File names in the bundled folder, one per line, so only sprites that are there get looked
+/// up instead of every request on web failing first.
+const BUNDLED_INDEX: &str = include_str!("../assets/sprites/index.txt");
+
+fn is_bundled(file_name: &str) -> bool {
+    BUNDLED_INDEX
+        .lines()
+        .any(|line| !line.starts_with('#') && line.trim() == file_name)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpriteKey {
+    pub species: u16,
+    pub form: u8,
+    pub shiny: bool,
+}
+
+impl SpriteKey {
+    pub fn new(species: u16, form: u8) -> Self {
+        Self {
+            species,
+            form,
+            shiny: false,
+        }
+    }
+
+    /// Shiny sprite for always-shiny encounters, the normal one otherwise.
+    pub fn for_encounter(encounter: &RaidEncounter) -> Self {
+        Self {
+            shiny: encounter.shiny == ShinyType::Always,
+            ..Self::new(encounter.species, encounter.form)
+        }
+    }
+
+    /// The sprite to show when this one does not exist: the normal colours of a shiny
+    /// sprite, then the base form of an alternate form.
+    fn fallback(self) -> Option<Self> {
+        if self.shiny {
+            Some(Self {
+                shiny: false,
+                ..self
+            })
+        } else if self.form != 0 {
+            Some(Self::new(self.species, 0))
+        } else {
+            None
+        }
+    }
+
+    fn file_name(self) -> String {
+        let shiny = if self.shiny { "s" } else { "" };
+        if self.form == 0 {
+            format!("{}{}.png", self.species, shiny)
+        } else {
+            format!("{}-{}{}.png", self.species, self.form, shiny)
+        }
+    }
+
+    fn remote_url(self) -> Option<String> {
+        if !self.shiny {
+            Some(format!("{}{}", SPRITE_URL, self.file_name()))
+        } else if self.form == 0 {
+            Some(format!("{}{}.png", SHINY_SPRITE_URL, self.species))
+        } else {
+            let suffix = form_suffix(self.species, self.form)?;
+            let species = names::english(&SPECIES, self.species as usize);
+            Some(format!(
+                "{}{}-{}.png",
+                SHINY_FORM_SPRITE_URL,
+                showdown_id(species),
+                showdown_id(suffix)
+            ))
+        }
+    }
+}
+
+/// Lowercase letters and digits only, the way Showdown names its sprites.
+fn showdown_id(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[derive(Clone)]
+enum SpriteState {
+    Loading,
+    Loaded(Arc<RetainedImage>),
+    Missing,
+}
+
+/// Loads sprites once and keeps them for the rest of the session. Bundled sprites are used
+/// first, then the disk cache on native, then the sprite repository. Downloads are written
+/// back to the disk cache on native; on web the service worker keeps them in browser storage.
+#[derive(Clone, Default)]
+pub struct SpriteCache {
+    sprites: Arc<Mutex<HashMap<SpriteKey, SpriteState>>>,
+}
+
+impl SpriteCache {
+    /// The sprite for `key`, starting a load if it has not been requested yet. Missing shiny
+    /// sprites fall back to the normal colours and missing forms to the base form.
+    pub fn get(&self, key: SpriteKey, ctx: &Context) -> Option<Arc<RetainedImage>> {
+        match self.state(key, ctx) {
+            Some(SpriteState::Loaded(image)) => Some(image),
+            Some(SpriteState::Missing) => self.get(key.fallback()?, ctx),
+            _ => None,
+        }
+    }
+
+    /// Whether there is a shiny sprite for `key`'s species and form, rather than only the
+    /// normal colours it would fall back to. Starts loading it if it has not been requested yet.
+    pub fn has_shiny(&self, key: SpriteKey, ctx: &Context) -> bool {
+        let shiny = SpriteKey { shiny: true, ..key };
+        matches!(self.state(shiny, ctx), Some(SpriteState::Loaded(_)))
+    }
+
+    /// Current state of a sprite, or `None` if this call started loading it.
+    fn state(&self, key: SpriteKey, ctx: &Context) -> Option<SpriteState> {
+        let state = match self.sprites.lock() {
+            Ok(mut sprites) => match sprites.get(&key) {
+                Some(state) => Some(state.clone()),
+                None => {
+                    sprites.insert(key, SpriteState::Loading);
+                    None
+                }
+            },
+            Err(_) => return Some(SpriteState::Missing),
+        };
+        if state.is_none() {
+            self.load(key, ctx);
+        }
+        state
+    }
+
+    fn finish(&self, key: SpriteKey, state: SpriteState, ctx: &Context) {
+        if let Ok(mut sprites) = self.sprites.lock() {
+            sprites.insert(key, state);
+        }
+        ctx.request_repaint();
+    }
+
+    fn load(&self, key: SpriteKey, ctx: &Context) {
+        let file_name = key.file_name();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let bundled = is_bundled(&file_name).then(|| bundled_dir().join(&file_name));
+            let local = bundled
+                .into_iter()
+                .chain(cache_dir().map(|dir| dir.join(&file_name)));
+            for path in local {
+                if let Ok(bytes) = std::fs::read(&path) {
+                    if let Ok(image) = RetainedImage::from_image_bytes(&file_name, &bytes) {
+                        self.finish(key, SpriteState::Loaded(Arc::new(image)), ctx);
+                        return;
+                    }
+                }
+            }
+        }
+
+        // On web the bundled sprites are served next to the app.
+        let bundled = (cfg!(target_arch = "wasm32") && is_bundled(&file_name))
+            .then(|| format!("./sprites/{}", file_name));
+        let urls = bundled.into_iter().chain(key.remote_url()).collect();
+        self.fetch(key, urls, ctx.clone());
+    }
+
+    /// Tries each URL in turn until one returns an image.
+    fn fetch(&self, key: SpriteKey, mut urls: Vec<String>, ctx: Context) {
+        if urls.is_empty() {
+            self.finish(key, SpriteState::Missing, &ctx);
+            return;
+        }
+        let url = urls.remove(0);
+        let cache = self.clone();
+        ehttp::fetch(ehttp::Request::get(url), move |response| {
+            let image = response.ok().filter(|r| r.ok).and_then(|response| {
+                let image = RetainedImage::from_image_bytes(&response.url, &response.bytes).ok()?;
+                #[cfg(not(target_arch = "wasm32"))]
+                save_to_cache(key, &response.bytes);
+                Some(image)
+            });
+            match image {
+                Some(image) => cache.finish(key, SpriteState::Loaded(Arc::new(image)), &ctx),
+                None => cache.fetch(key, urls, ctx),
+            }
+        });
+    }
+}
+
+/// Shows a sprite no larger than `max_size`, with a Tera crystal in the colour of `tera_type`
+/// in its lower right corner.
+pub fn show_sprite(
+    ui: &mut Ui,
+    sprite: &RetainedImage,
+    tera_type: Option<usize>,
+    max_size: Vec2,
+) -> Response {
+    let response = sprite.show_max_size(ui, max_size);
+    if let Some(color) = tera_type.and_then(|t| TYPE_COLORS.get(t)) {
+        let height = response.rect.height() * 0.35;
+        let rect =
+            Rect::from_min_size(response.rect.max - Vec2::splat(height), Vec2::splat(height));
+        let (left, right) = (rect.left() + height * 0.2, rect.right() - height * 0.2);
+        let (top, girdle, bottom) = (rect.top(), rect.top() + height * 0.35, rect.bottom());
+        let center = rect.center().x;
+        let stroke = Stroke::new(1.0, Color32::WHITE);
+        ui.painter().add(Shape::convex_polygon(
+            vec![
+                pos2(center, top),
+                pos2(right, girdle),
+                pos2(center, bottom),
+                pos2(left, girdle),
+            ],
+            *color,
+            stroke,
+        ));
+        // Facets so the gem reads as a crystal rather than a flat diamond.
+        ui.painter()
+            .line_segment([pos2(left, girdle), pos2(right, girdle)], stroke);
+        ui.painter()
+            .line_segment([pos2(center, top), pos2(center, bottom)], stroke);
+    }
+    response
+}
+
+/// The bundled folder next to the executable, or in the working directory when running from
+/// a checkout with `cargo run`.
+#[cfg(not(target_arch = "wasm32"))]
+fn bundled_dir() -> std::path::PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(BUNDLED_DIR)))
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(|| std::path::PathBuf::from(BUNDLED_DIR))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn cache_dir() -> Option<std::path::PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(std::path::PathBuf::from))
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".cache"))
+        })?;
+    Some(base.join("svraid_lookup").join("sprites"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_to_cache(key: SpriteKey, bytes: &[u8]) {
+    if let Some(dir) = cache_dir() {
+        if std::fs::create_dir_all(&dir).is_ok() {
+            let _ = std::fs::write(dir.join(key.file_name()), bytes);
+        }
+    }
+}
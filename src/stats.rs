@@ -0,0 +1,236 @@
+use crate::encounter_details::{EncounterDetails, IvSpec};
+use crate::i18n::tr;
+use crate::names;
+use eframe::egui;
+use eframe::egui::Ui;
+use sv_raid_reader::{personal_table, NATURES};
+
+pub const STAT_LABELS: [&str; 6] = ["HP", "Atk", "Def", "SpA", "SpD", "Spe"];
+
+/// Base stats in HP/Atk/Def/SpA/SpD/Spe order.
+pub fn base_stats(species: u16, form: u8) -> [u16; 6] {
+    let mut base = [0u16; 6];
+    let stats = personal_table::SV
+        .get_form_entry(species as usize, form as usize)
+        .stats();
+    for (b, s) in base.iter_mut().zip(stats) {
+        *b = s as u16;
+    }
+    // Personal data stores speed before the special stats.
+    base.swap(3, 4);
+    base.swap(4, 5);
+    base
+}
+
+/// Multiplier in tenths that a nature applies to a stat in HP/Atk/Def/SpA/SpD/Spe order.
+pub fn nature_multiplier(nature: usize, stat: usize) -> u32 {
+    // Natures are ordered by the stat they raise then lower, using Atk/Def/Spe/SpA/SpD.
+    const NATURE_ORDER: [usize; 5] = [1, 2, 5, 3, 4];
+    let up = NATURE_ORDER[nature / 5];
+    let down = NATURE_ORDER[nature % 5];
+    if up == down {
+        10
+    } else if stat == up {
+        11
+    } else if stat == down {
+        9
+    } else {
+        10
+    }
+}
+
+/// Actual stats at `level` with no EVs, in HP/Atk/Def/SpA/SpD/Spe order.
+pub fn calc_stats(base: [u16; 6], ivs: [u8; 6], level: u8, nature: usize) -> [u16; 6] {
+    calc_stats_with_evs(base, ivs, [0; 6], level, nature)
+}
+
+/// Actual stats at `level`, in HP/Atk/Def/SpA/SpD/Spe order.
+pub fn calc_stats_with_evs(
+    base: [u16; 6],
+    ivs: [u8; 6],
+    evs: [u16; 6],
+    level: u8,
+    nature: usize,
+) -> [u16; 6] {
+    let level = level as u32;
+    let mut stats = [0u16; 6];
+    for (i, stat) in stats.iter_mut().enumerate() {
+        let raw = (2 * base[i] as u32 + ivs[i] as u32 + evs[i] as u32 / 4) * level / 100;
+        *stat = if i == 0 {
+            raw + level + 10
+        } else {
+            (raw + 5) * nature_multiplier(nature, i) / 10
+        } as u16;
+    }
+    stats
+}
+
+/// Lets the user pick IVs and nature for the parts of the build the encounter leaves random,
+/// and shows the boss' real stats, raid HP and the HP at which it raises its shield.
+#[derive(Clone)]
+pub struct StatCalculator {
+    pub base: [u16; 6],
+    pub level: u8,
+    pub hp_coef: u16,
+    pub shield_trigger_hp: u8,
+    pub ivs: [u8; 6],
+    pub fixed_ivs: bool,
+    pub nature: usize,
+    pub fixed_nature: bool,
+}
+
+impl StatCalculator {
+    pub fn new(details: &EncounterDetails) -> Self {
+        let fixed_ivs = match details.ivs {
+            IvSpec::Fixed(ivs) => Some(ivs),
+            IvSpec::Perfect(_) | IvSpec::Random => None,
+        };
+        Self {
+            base: details.base_stats,
+            level: details.level,
+            hp_coef: details.hp_coef,
+            shield_trigger_hp: details.shield_trigger_hp,
+            ivs: fixed_ivs.unwrap_or([31; 6]),
+            fixed_ivs: fixed_ivs.is_some(),
+            nature: details.nature.unwrap_or(0),
+            fixed_nature: details.nature.is_some(),
+        }
+    }
+
+    pub fn stats(&self) -> [u16; 6] {
+        calc_stats(self.base, self.ivs, self.level, self.nature)
+    }
+
+    pub fn raid_hp(&self) -> u32 {
+        self.stats()[0] as u32 * self.hp_coef as u32
+    }
+
+    pub fn shield_hp(&self) -> Option<u32> {
+        (self.shield_trigger_hp != 0).then(|| self.raid_hp() * self.shield_trigger_hp as u32 / 100)
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let stats = self.stats();
+        egui::Grid::new("stat_calculator").show(ui, |ui| {
+            ui.label("");
+            for label in STAT_LABELS {
+                ui.label(label);
+            }
+            ui.end_row();
+
+            ui.label(tr("IVs"));
+            for iv in self.ivs.iter_mut() {
+                if self.fixed_ivs {
+                    ui.label(iv.to_string());
+                } else {
+                    ui.add(egui::DragValue::new(iv).clamp_range(0..=31));
+                }
+            }
+            ui.end_row();
+
+            ui.label(format!("{} {}", tr("Lv."), self.level));
+            for stat in stats {
+                ui.label(stat.to_string());
+            }
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Nature:"));
+            if self.fixed_nature {
+                ui.label(names::nature(self.nature));
+            } else {
+                egui::ComboBox::from_id_source("stat_nature")
+                    .selected_text(names::nature(self.nature))
+                    .show_ui(ui, |ui| {
+                        for i in 0..NATURES.len() {
+                            ui.selectable_value(&mut self.nature, i, names::nature(i));
+                        }
+                    });
+            }
+        });
+
+        ui.label(format!(
+            "{} {} ({} × {})",
+            tr("Raid HP:"),
+            self.raid_hp(),
+            stats[0],
+            self.hp_coef
+        ));
+        if let Some(shield_hp) = self.shield_hp() {
+            ui.label(format!(
+                "{} {} {} ({}%)",
+                tr("Shield at:"),
+                shield_hp,
+                tr("HP"),
+                self.shield_trigger_hp
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HARDY: usize = 0;
+    const ADAMANT: usize = 3;
+    const MODEST: usize = 15;
+    const DRAGONITE: [u16; 6] = [91, 134, 95, 100, 100, 80];
+
+    #[test]
+    fn orders_base_stats_like_the_games() {
+        assert_eq!(base_stats(149, 0), DRAGONITE);
+    }
+
+    #[test]
+    fn applies_natures() {
+        // Adamant raises Atk and lowers SpA, Modest the other way round.
+        assert_eq!(nature_multiplier(ADAMANT, 1), 11);
+        assert_eq!(nature_multiplier(ADAMANT, 3), 9);
+        assert_eq!(nature_multiplier(ADAMANT, 5), 10);
+        assert_eq!(nature_multiplier(MODEST, 3), 11);
+        assert_eq!(nature_multiplier(MODEST, 1), 9);
+        // Neutral natures and HP are never changed.
+        assert!((0..6).all(|stat| nature_multiplier(HARDY, stat) == 10));
+        assert!((0..25).all(|nature| nature_multiplier(nature, 0) == 10));
+    }
+
+    #[test]
+    fn calculates_boss_stats() {
+        let ivs = [31; 6];
+        assert_eq!(
+            calc_stats(DRAGONITE, ivs, 90, HARDY),
+            [291, 274, 203, 212, 212, 176]
+        );
+        assert_eq!(
+            calc_stats(DRAGONITE, ivs, 90, ADAMANT),
+            [291, 301, 203, 190, 212, 176]
+        );
+        assert_eq!(
+            calc_stats(DRAGONITE, ivs, 90, MODEST),
+            [291, 246, 203, 233, 212, 176]
+        );
+    }
+
+    #[test]
+    fn multiplies_raid_hp() {
+        let boss = StatCalculator {
+            base: DRAGONITE,
+            level: 90,
+            hp_coef: 35,
+            shield_trigger_hp: 70,
+            ivs: [31; 6],
+            fixed_ivs: true,
+            nature: HARDY,
+            fixed_nature: true,
+        };
+        assert_eq!(boss.raid_hp(), 10185);
+        assert_eq!(boss.shield_hp(), Some(7129));
+        let no_shield = StatCalculator {
+            shield_trigger_hp: 0,
+            ..boss
+        };
+        assert_eq!(no_shield.shield_hp(), None);
+    }
+}
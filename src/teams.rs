@@ -0,0 +1,320 @@
+use crate::i18n::tr;
+use crate::matchup::Matchup;
+use crate::names;
+use crate::showdown::{parse_showdown_team, showdown_species, ShowdownSet};
+use crate::stats::{base_stats, calc_stats_with_evs, StatCalculator};
+use crate::types::{base_types, effectiveness_against, move_kind_by_name};
+use eframe::egui;
+use eframe::egui::{Color32, RichText, Ui};
+
+/// Key the saved teams are stored under in eframe's storage.
+pub const STORAGE_KEY: &str = "teams";
+
+/// A named team, kept as the Showdown text it was imported from.
+#[derive(Clone)]
+pub struct Team {
+    pub name: String,
+    pub paste: String,
+    pub sets: Vec<ShowdownSet>,
+    pub errors: Vec<String>,
+}
+
+impl Team {
+    pub fn new(name: &str, paste: &str) -> Self {
+        let mut sets = vec![];
+        let mut errors = vec![];
+        for set in parse_showdown_team(paste) {
+            match set {
+                Ok(set) => sets.push(set),
+                Err(error) => errors.push(error),
+            }
+        }
+        Self {
+            name: name.trim().to_string(),
+            paste: paste.trim().to_string(),
+            sets,
+            errors,
+        }
+    }
+
+    pub fn scores(&self, boss: &StatCalculator, matchup: &Matchup) -> Vec<MemberScore> {
+        self.sets
+            .iter()
+            .map(|set| MemberScore::new(set, boss, matchup))
+            .collect()
+    }
+}
+
+/// Index of the highest scoring member.
+fn best_score(scores: &[MemberScore]) -> Option<usize> {
+    scores
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.score.total_cmp(&b.1.score))
+        .map(|(i, _)| i)
+}
+
+/// How one team member fares against the selected raid.
+pub struct MemberScore {
+    pub name: String,
+    /// Best multiplier of its damaging moves against the boss.
+    pub offense: f32,
+    pub offense_type: Option<usize>,
+    /// Worst multiplier it takes from the boss' moves, with its Tera type if it has one.
+    pub worst_taken: f32,
+    pub speed: u16,
+    pub outspeeds: bool,
+    /// Rough bulk against the boss' better attacking stat, higher is sturdier.
+    pub survivability: f32,
+    pub score: f32,
+}
+
+impl MemberScore {
+    pub fn new(set: &ShowdownSet, boss: &StatCalculator, matchup: &Matchup) -> Self {
+        let stats = calc_stats_with_evs(
+            base_stats(set.species, set.form),
+            set.ivs,
+            set.evs,
+            set.level,
+            set.nature,
+        );
+        let boss_stats = boss.stats();
+
+        let mut move_types = set
+            .moves
+            .iter()
+            .filter_map(|m| move_kind_by_name(m)?.resolve_for_player(set.tera_type))
+            .collect::<Vec<_>>();
+        // Without any known moves, judge it by its STAB and Tera type.
+        if move_types.is_empty() {
+            move_types = base_types(set.species, set.form);
+            move_types.extend(set.tera_type);
+        }
+        let (offense_type, offense) = move_types
+            .iter()
+            .map(|&t| (Some(t), effectiveness_against(t, &matchup.defense_types)))
+            .fold(
+                (None, 0.0),
+                |best, next| if next.1 > best.1 { next } else { best },
+            );
+
+        let defense_types = match set.tera_type {
+            Some(tera) => vec![tera],
+            None => base_types(set.species, set.form),
+        };
+        let worst_taken = matchup
+            .boss_move_types()
+            .iter()
+            .map(|&t| effectiveness_against(t, &defense_types))
+            .fold(0.0, f32::max);
+
+        let physical = stats[0] as f32 * stats[2] as f32 / boss_stats[1].max(1) as f32;
+        let special = stats[0] as f32 * stats[4] as f32 / boss_stats[3].max(1) as f32;
+        let survivability = physical.min(special) / 100.0 / worst_taken.max(0.25);
+
+        let outspeeds = stats[5] > boss_stats[5];
+        let attack = stats[1].max(stats[3]) as f32;
+        let score = offense * attack * survivability.sqrt() * if outspeeds { 1.1 } else { 1.0 };
+
+        Self {
+            name: showdown_species(set.species, set.form),
+            offense,
+            offense_type,
+            worst_taken,
+            speed: stats[5],
+            outspeeds,
+            survivability,
+            score,
+        }
+    }
+}
+
+/// Saved teams and the one being scored against the selected raid.
+#[derive(Default)]
+pub struct TeamRoster {
+    pub teams: Vec<Team>,
+    pub selected: Option<usize>,
+    pub name: String,
+    pub paste: String,
+}
+
+impl TeamRoster {
+    /// Reads teams stored in Showdown's backup format, where each team starts with a
+    /// `=== Name ===` line.
+    pub fn load(text: &str) -> Self {
+        let mut teams = vec![];
+        let mut current: Option<(String, String)> = None;
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if let Some(name) = trimmed
+                .strip_prefix("===")
+                .and_then(|rest| rest.strip_suffix("==="))
+            {
+                if let Some((name, paste)) = current.take() {
+                    teams.push(Team::new(&name, &paste));
+                }
+                current = Some((name.trim().to_string(), String::new()));
+            } else if let Some((_, paste)) = current.as_mut() {
+                paste.push_str(line);
+                paste.push('\n');
+            }
+        }
+        if let Some((name, paste)) = current {
+            teams.push(Team::new(&name, &paste));
+        }
+        Self {
+            selected: (!teams.is_empty()).then_some(0),
+            teams,
+            ..Default::default()
+        }
+    }
+
+    pub fn save(&self) -> String {
+        self.teams
+            .iter()
+            .map(|team| format!("=== {} ===\n\n{}\n\n", team.name, team.paste))
+            .collect()
+    }
+
+    fn save_team(&mut self) {
+        let name = if self.name.trim().is_empty() {
+            format!("{} {}", tr("Team"), self.teams.len() + 1)
+        } else {
+            self.name.clone()
+        };
+        let team = Team::new(&name, &self.paste);
+        match self.teams.iter().position(|t| t.name == team.name) {
+            Some(i) => {
+                self.teams[i] = team;
+                self.selected = Some(i);
+            }
+            None => {
+                self.teams.push(team);
+                self.selected = Some(self.teams.len() - 1);
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, boss: &StatCalculator, matchup: &Matchup) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Team:"));
+            egui::ComboBox::from_id_source("saved_teams")
+                .selected_text(
+                    self.selected
+                        .and_then(|i| self.teams.get(i))
+                        .map_or(tr("None"), |t| t.name.as_str()),
+                )
+                .show_ui(ui, |ui| {
+                    for (i, team) in self.teams.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected, Some(i), team.name.as_str());
+                    }
+                });
+            if let Some(i) = self.selected {
+                if ui.button(tr("Edit")).clicked() {
+                    self.name = self.teams[i].name.clone();
+                    self.paste = self.teams[i].paste.clone();
+                }
+                if ui.button(tr("Delete")).clicked() {
+                    self.teams.remove(i);
+                    self.selected = (!self.teams.is_empty()).then_some(0);
+                }
+            }
+        });
+
+        egui::CollapsingHeader::new(tr("Import Team")).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("Name:"));
+                ui.text_edit_singleline(&mut self.name);
+            });
+            ui.add(
+                egui::TextEdit::multiline(&mut self.paste)
+                    .desired_rows(6)
+                    .hint_text(tr("Paste a Showdown team")),
+            );
+            if ui.button(tr("Save Team")).clicked() {
+                self.save_team();
+            }
+        });
+
+        let team = match self.selected.and_then(|i| self.teams.get(i)) {
+            Some(team) => team,
+            None => {
+                ui.label(tr("Import a team to score it against this raid."));
+                return;
+            }
+        };
+        for error in team.errors.iter() {
+            ui.colored_label(Color32::RED, error);
+        }
+
+        let scores = team.scores(boss, matchup);
+        let best = best_score(&scores);
+
+        egui::Grid::new("team_scores").striped(true).show(ui, |ui| {
+            for header in ["Pokémon", "Offense", "Takes", "Speed", "Bulk", "Score"] {
+                ui.label(tr(header));
+            }
+            ui.end_row();
+
+            for (i, score) in scores.iter().enumerate() {
+                let name = RichText::new(&score.name);
+                ui.label(if Some(i) == best {
+                    name.strong().color(Color32::GOLD)
+                } else {
+                    name
+                });
+                ui.label(format!(
+                    "{}x {}",
+                    score.offense,
+                    score.offense_type.map_or("", names::type_name)
+                ));
+                ui.label(format!("{}x", score.worst_taken));
+                ui.label(format!(
+                    "{} ({})",
+                    score.speed,
+                    tr(if score.outspeeds { "faster" } else { "slower" })
+                ));
+                ui.label(format!("{:.1}", score.survivability));
+                ui.label(format!("{:.0}", score.score));
+                ui.end_row();
+            }
+        });
+
+        if self.teams.len() > 1 {
+            ui.add_space(5.0);
+            ui.strong(tr("Best pick from each team"));
+            let picks = self
+                .teams
+                .iter()
+                .filter_map(|team| {
+                    let mut scores = team.scores(boss, matchup);
+                    let best = best_score(&scores)?;
+                    Some((team.name.as_str(), scores.swap_remove(best)))
+                })
+                .collect::<Vec<_>>();
+            let overall = picks
+                .iter()
+                .enumerate()
+                .max_by(|(_, (_, a)), (_, (_, b))| a.score.total_cmp(&b.score))
+                .map(|(i, _)| i);
+            egui::Grid::new("team_best_picks")
+                .striped(true)
+                .show(ui, |ui| {
+                    for (i, (team, score)) in picks.iter().enumerate() {
+                        let name = RichText::new(&score.name);
+                        ui.label(*team);
+                        ui.label(if Some(i) == overall {
+                            name.strong().color(Color32::GOLD)
+                        } else {
+                            name
+                        });
+                        ui.label(format!("{:.0}", score.score));
+                        ui.end_row();
+                    }
+                });
+        }
+        ui.small(tr(
+            "Scores use typing, stats and move types only; abilities and items are ignored.",
+        ));
+    }
+}
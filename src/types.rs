@@ -0,0 +1,894 @@
+use eframe::egui::Color32;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use sv_raid_reader::{personal_table, GemType, MOVES};
+
+/// Tera types in the same order as `TYPES`.
+pub const GEM_TYPES: [GemType; 18] = [
+    GemType::Normal,
+    GemType::Fighting,
+    GemType::Flying,
+    GemType::Poison,
+    GemType::Ground,
+    GemType::Rock,
+    GemType::Bug,
+    GemType::Ghost,
+    GemType::Steel,
+    GemType::Fire,
+    GemType::Water,
+    GemType::Grass,
+    GemType::Electric,
+    GemType::Psychic,
+    GemType::Ice,
+    GemType::Dragon,
+    GemType::Dark,
+    GemType::Fairy,
+];
+
+pub const TYPE_COUNT: usize = GEM_TYPES.len();
+
+const NORMAL: usize = 0;
+
+/// Badge and Tera crystal colours for each type, in the same order as `TYPES`.
+pub const TYPE_COLORS: [Color32; TYPE_COUNT] = [
+    Color32::from_rgb(0xA8, 0xA7, 0x7A),
+    Color32::from_rgb(0xC2, 0x2E, 0x28),
+    Color32::from_rgb(0xA9, 0x8F, 0xF3),
+    Color32::from_rgb(0xA3, 0x3E, 0xA1),
+    Color32::from_rgb(0xE2, 0xBF, 0x65),
+    Color32::from_rgb(0xB6, 0xA1, 0x36),
+    Color32::from_rgb(0xA6, 0xB9, 0x1A),
+    Color32::from_rgb(0x73, 0x57, 0x97),
+    Color32::from_rgb(0xB7, 0xB7, 0xCE),
+    Color32::from_rgb(0xEE, 0x81, 0x30),
+    Color32::from_rgb(0x63, 0x90, 0xF0),
+    Color32::from_rgb(0x7A, 0xC7, 0x4C),
+    Color32::from_rgb(0xF7, 0xD0, 0x2C),
+    Color32::from_rgb(0xF9, 0x55, 0x87),
+    Color32::from_rgb(0x96, 0xD9, 0xD6),
+    Color32::from_rgb(0x6F, 0x35, 0xFC),
+    Color32::from_rgb(0x70, 0x57, 0x46),
+    Color32::from_rgb(0xD6, 0x85, 0xAD),
+];
+
+/// Effectiveness in halves, indexed by attacking type then defending type.
+const CHART: [[u8; TYPE_COUNT]; TYPE_COUNT] = [
+    [2, 2, 2, 2, 2, 1, 2, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2], // Normal
+    [4, 2, 1, 1, 2, 4, 1, 0, 4, 2, 2, 2, 2, 1, 4, 2, 4, 1], // Fighting
+    [2, 4, 2, 2, 2, 1, 4, 2, 1, 2, 2, 4, 1, 2, 2, 2, 2, 2], // Flying
+    [2, 2, 2, 1, 1, 1, 2, 1, 0, 2, 2, 4, 2, 2, 2, 2, 2, 4], // Poison
+    [2, 2, 0, 4, 2, 4, 1, 2, 4, 4, 2, 1, 4, 2, 2, 2, 2, 2], // Ground
+    [2, 1, 4, 2, 1, 2, 4, 2, 1, 4, 2, 2, 2, 2, 4, 2, 2, 2], // Rock
+    [2, 1, 1, 1, 2, 2, 2, 1, 1, 1, 2, 4, 2, 4, 2, 2, 4, 1], // Bug
+    [0, 2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 4, 2, 2, 1, 2], // Ghost
+    [2, 2, 2, 2, 2, 4, 2, 2, 1, 1, 1, 2, 1, 2, 4, 2, 2, 4], // Steel
+    [2, 2, 2, 2, 2, 1, 4, 2, 4, 1, 1, 4, 2, 2, 4, 1, 2, 2], // Fire
+    [2, 2, 2, 2, 4, 4, 2, 2, 2, 4, 1, 1, 2, 2, 2, 1, 2, 2], // Water
+    [2, 2, 1, 1, 4, 4, 1, 2, 1, 1, 4, 1, 2, 2, 2, 1, 2, 2], // Grass
+    [2, 2, 4, 2, 0, 2, 2, 2, 2, 2, 4, 1, 1, 2, 2, 1, 2, 2], // Electric
+    [2, 4, 2, 4, 2, 2, 2, 2, 1, 2, 2, 2, 2, 1, 2, 2, 0, 2], // Psychic
+    [2, 2, 4, 2, 4, 2, 2, 2, 1, 1, 1, 4, 2, 2, 1, 4, 2, 2], // Ice
+    [2, 2, 2, 2, 2, 2, 2, 2, 1, 2, 2, 2, 2, 2, 2, 4, 2, 0], // Dragon
+    [2, 1, 2, 2, 2, 2, 2, 4, 2, 2, 2, 2, 2, 4, 2, 2, 1, 1], // Dark
+    [2, 4, 2, 1, 2, 2, 2, 2, 1, 1, 2, 2, 2, 2, 2, 4, 4, 2], // Fairy
+];
+
+/// Index into `TYPES` for a Tera type, `None` for a random Tera type.
+pub fn gem_type_index(gem_type: GemType) -> Option<usize> {
+    GEM_TYPES.iter().position(|&t| t == gem_type)
+}
+
+pub fn effectiveness(attack: usize, defend: usize) -> f32 {
+    CHART[attack][defend] as f32 / 2.0
+}
+
+pub fn effectiveness_against(attack: usize, defend: &[usize]) -> f32 {
+    defend.iter().map(|&d| effectiveness(attack, d)).product()
+}
+
+/// Distinct base types of a species form.
+pub fn base_types(species: u16, form: u8) -> Vec<usize> {
+    let personal = personal_table::SV.get_form_entry(species as usize, form as usize);
+    let type_1 = personal.get_type_1();
+    let type_2 = personal.get_type_2();
+    if type_1 != type_2 && type_2 < TYPE_COUNT {
+        vec![type_1, type_2]
+    } else {
+        vec![type_1]
+    }
+}
+
+/// Names of damaging moves for each type, in the same order as `TYPES`. Move data is not part
+/// of the raid tables, so this is matched against `MOVES` once to find each move's type by ID.
+/// Moves whose type can change are in [`TERA_MOVES`] and [`VARIABLE_MOVES`] instead.
+const DAMAGING_MOVES: [&[&str]; TYPE_COUNT] = [
+    // Normal
+    &[
+        "Pound",
+        "Double Slap",
+        "Comet Punch",
+        "Mega Punch",
+        "Pay Day",
+        "Scratch",
+        "Vise Grip",
+        "Cut",
+        "Slam",
+        "Stomp",
+        "Mega Kick",
+        "Headbutt",
+        "Horn Attack",
+        "Fury Attack",
+        "Tackle",
+        "Body Slam",
+        "Wrap",
+        "Take Down",
+        "Thrash",
+        "Double-Edge",
+        "Hyper Fang",
+        "Super Fang",
+        "Sonic Boom",
+        "Quick Attack",
+        "Rage",
+        "Swift",
+        "Skull Bash",
+        "Hyper Beam",
+        "Strength",
+        "Explosion",
+        "Self-Destruct",
+        "Fury Swipes",
+        "Tri Attack",
+        "Slash",
+        "Bind",
+        "Barrage",
+        "Constrict",
+        "Dizzy Punch",
+        "Egg Bomb",
+        "Spike Cannon",
+        "Snore",
+        "Return",
+        "Frustration",
+        "Rapid Spin",
+        "False Swipe",
+        "Fake Out",
+        "Uproar",
+        "Facade",
+        "Crush Claw",
+        "Extreme Speed",
+        "Endeavor",
+        "Secret Power",
+        "Hyper Voice",
+        "Covet",
+        "Giga Impact",
+        "Last Resort",
+        "Crush Grip",
+        "Wring Out",
+        "Trump Card",
+        "Round",
+        "Echoed Voice",
+        "Retaliate",
+        "Chip Away",
+        "Head Charge",
+        "Relic Song",
+        "Boomburst",
+        "Hold Back",
+        "Double Hit",
+        "Tail Slap",
+        "Hyper Drill",
+        "Population Bomb",
+        "Struggle",
+    ],
+    // Fighting
+    &[
+        "Karate Chop",
+        "Double Kick",
+        "Jump Kick",
+        "Rolling Kick",
+        "Submission",
+        "Low Kick",
+        "Counter",
+        "Seismic Toss",
+        "High Jump Kick",
+        "Triple Kick",
+        "Mach Punch",
+        "Dynamic Punch",
+        "Cross Chop",
+        "Vital Throw",
+        "Rock Smash",
+        "Superpower",
+        "Brick Break",
+        "Arm Thrust",
+        "Sky Uppercut",
+        "Reversal",
+        "Focus Punch",
+        "Hammer Arm",
+        "Close Combat",
+        "Force Palm",
+        "Aura Sphere",
+        "Drain Punch",
+        "Vacuum Wave",
+        "Focus Blast",
+        "Low Sweep",
+        "Storm Throw",
+        "Circle Throw",
+        "Sacred Sword",
+        "Secret Sword",
+        "Flying Press",
+        "Power-Up Punch",
+        "Body Press",
+        "Meteor Assault",
+        "Thunderous Kick",
+        "Axe Kick",
+        "Triple Arrows",
+        "Collision Course",
+        "Combat Torque",
+        "Final Gambit",
+        "Upper Hand",
+    ],
+    // Flying
+    &[
+        "Gust",
+        "Wing Attack",
+        "Fly",
+        "Peck",
+        "Drill Peck",
+        "Sky Attack",
+        "Aerial Ace",
+        "Air Slash",
+        "Brave Bird",
+        "Bounce",
+        "Air Cutter",
+        "Pluck",
+        "Acrobatics",
+        "Hurricane",
+        "Sky Drop",
+        "Chatter",
+        "Aeroblast",
+        "Dragon Ascent",
+        "Oblivion Wing",
+        "Beak Blast",
+        "Dual Wingbeat",
+        "Bleakwind Storm",
+    ],
+    // Poison
+    &[
+        "Poison Sting",
+        "Acid",
+        "Sludge",
+        "Smog",
+        "Poison Fang",
+        "Sludge Bomb",
+        "Poison Jab",
+        "Cross Poison",
+        "Gunk Shot",
+        "Poison Tail",
+        "Sludge Wave",
+        "Venoshock",
+        "Clear Smog",
+        "Acid Spray",
+        "Belch",
+        "Shell Side Arm",
+        "Dire Claw",
+        "Barb Barrage",
+        "Mortal Spin",
+        "Noxious Torque",
+        "Malignant Chain",
+    ],
+    // Ground
+    &[
+        "Earthquake",
+        "Fissure",
+        "Dig",
+        "Bone Club",
+        "Bonemerang",
+        "Mud-Slap",
+        "Magnitude",
+        "Mud Shot",
+        "Mud Bomb",
+        "Earth Power",
+        "Bulldoze",
+        "Drill Run",
+        "Bone Rush",
+        "Thousand Arrows",
+        "Thousand Waves",
+        "Land's Wrath",
+        "High Horsepower",
+        "Precipice Blades",
+        "Stomping Tantrum",
+        "Scorching Sands",
+        "Headlong Rush",
+        "Sandsear Storm",
+    ],
+    // Rock
+    &[
+        "Rock Throw",
+        "Rock Slide",
+        "Rock Tomb",
+        "Rock Blast",
+        "Ancient Power",
+        "Rollout",
+        "Power Gem",
+        "Rock Wrecker",
+        "Stone Edge",
+        "Head Smash",
+        "Smack Down",
+        "Diamond Storm",
+        "Accelerock",
+        "Meteor Beam",
+        "Stone Axe",
+        "Salt Cure",
+        "Mighty Cleave",
+    ],
+    // Bug
+    &[
+        "Twineedle",
+        "Pin Missile",
+        "Leech Life",
+        "Fury Cutter",
+        "Megahorn",
+        "Signal Beam",
+        "Silver Wind",
+        "U-turn",
+        "Bug Bite",
+        "Bug Buzz",
+        "X-Scissor",
+        "Attack Order",
+        "Struggle Bug",
+        "Steamroller",
+        "Infestation",
+        "Fell Stinger",
+        "Lunge",
+        "First Impression",
+        "Pollen Puff",
+        "Skitter Smack",
+        "Pounce",
+    ],
+    // Ghost
+    &[
+        "Lick",
+        "Night Shade",
+        "Shadow Ball",
+        "Shadow Punch",
+        "Astonish",
+        "Shadow Claw",
+        "Shadow Sneak",
+        "Ominous Wind",
+        "Shadow Force",
+        "Hex",
+        "Phantom Force",
+        "Shadow Bone",
+        "Spirit Shackle",
+        "Moongeist Beam",
+        "Spectral Thief",
+        "Poltergeist",
+        "Astral Barrage",
+        "Bitter Malice",
+        "Infernal Parade",
+        "Last Respects",
+        "Rage Fist",
+    ],
+    // Steel
+    &[
+        "Steel Wing",
+        "Iron Tail",
+        "Meteor Mash",
+        "Metal Claw",
+        "Iron Head",
+        "Flash Cannon",
+        "Bullet Punch",
+        "Gyro Ball",
+        "Mirror Shot",
+        "Magnet Bomb",
+        "Metal Burst",
+        "Doom Desire",
+        "Heavy Slam",
+        "Gear Grind",
+        "Anchor Shot",
+        "Smart Strike",
+        "Sunsteel Strike",
+        "Double Iron Bash",
+        "Behemoth Blade",
+        "Behemoth Bash",
+        "Steel Beam",
+        "Steel Roller",
+        "Spin Out",
+        "Make It Rain",
+        "Gigaton Hammer",
+        "Tachyon Cutter",
+        "Hard Press",
+    ],
+    // Fire
+    &[
+        "Ember",
+        "Fire Punch",
+        "Flamethrower",
+        "Fire Blast",
+        "Fire Spin",
+        "Flame Wheel",
+        "Sacred Fire",
+        "Heat Wave",
+        "Eruption",
+        "Blaze Kick",
+        "Overheat",
+        "Blast Burn",
+        "Flare Blitz",
+        "Fire Fang",
+        "Lava Plume",
+        "Magma Storm",
+        "Flame Burst",
+        "Flame Charge",
+        "Incinerate",
+        "Inferno",
+        "Fire Pledge",
+        "Fiery Dance",
+        "Searing Shot",
+        "Blue Flare",
+        "Fusion Flare",
+        "Heat Crash",
+        "V-create",
+        "Mystical Fire",
+        "Shell Trap",
+        "Burn Up",
+        "Mind Blown",
+        "Fire Lash",
+        "Pyro Ball",
+        "Burning Jealousy",
+        "Raging Fury",
+        "Torch Song",
+        "Armor Cannon",
+        "Bitter Blade",
+        "Blazing Torque",
+        "Temper Flare",
+    ],
+    // Water
+    &[
+        "Water Gun",
+        "Hydro Pump",
+        "Surf",
+        "Bubble Beam",
+        "Waterfall",
+        "Crabhammer",
+        "Bubble",
+        "Clamp",
+        "Octazooka",
+        "Whirlpool",
+        "Dive",
+        "Muddy Water",
+        "Water Spout",
+        "Water Pulse",
+        "Brine",
+        "Aqua Tail",
+        "Aqua Jet",
+        "Hydro Cannon",
+        "Scald",
+        "Razor Shell",
+        "Water Pledge",
+        "Steam Eruption",
+        "Water Shuriken",
+        "Origin Pulse",
+        "Sparkling Aria",
+        "Liquidation",
+        "Snipe Shot",
+        "Fishious Rend",
+        "Flip Turn",
+        "Surging Strikes",
+        "Wave Crash",
+        "Jet Punch",
+        "Chilling Water",
+        "Aqua Step",
+        "Aqua Cutter",
+        "Triple Dive",
+        "Hydro Steam",
+    ],
+    // Grass
+    &[
+        "Vine Whip",
+        "Razor Leaf",
+        "Solar Beam",
+        "Petal Dance",
+        "Absorb",
+        "Mega Drain",
+        "Giga Drain",
+        "Leaf Blade",
+        "Magical Leaf",
+        "Bullet Seed",
+        "Needle Arm",
+        "Frenzy Plant",
+        "Energy Ball",
+        "Leaf Storm",
+        "Power Whip",
+        "Seed Bomb",
+        "Wood Hammer",
+        "Grass Knot",
+        "Seed Flare",
+        "Leaf Tornado",
+        "Horn Leech",
+        "Grass Pledge",
+        "Petal Blizzard",
+        "Trop Kick",
+        "Solar Blade",
+        "Leafage",
+        "Drum Beating",
+        "Snap Trap",
+        "Branch Poke",
+        "Apple Acid",
+        "Grav Apple",
+        "Grassy Glide",
+        "Chloroblast",
+        "Flower Trick",
+        "Trailblaze",
+        "Matcha Gotcha",
+        "Syrup Bomb",
+    ],
+    // Electric
+    &[
+        "Thunder Shock",
+        "Thunderbolt",
+        "Thunder",
+        "Thunder Punch",
+        "Spark",
+        "Zap Cannon",
+        "Shock Wave",
+        "Volt Tackle",
+        "Discharge",
+        "Thunder Fang",
+        "Charge Beam",
+        "Electro Ball",
+        "Volt Switch",
+        "Electroweb",
+        "Wild Charge",
+        "Bolt Strike",
+        "Fusion Bolt",
+        "Parabolic Charge",
+        "Nuzzle",
+        "Zing Zap",
+        "Plasma Fists",
+        "Bolt Beak",
+        "Overdrive",
+        "Rising Voltage",
+        "Thunder Cage",
+        "Wildbolt Storm",
+        "Electro Drift",
+        "Double Shock",
+        "Electro Shot",
+        "Supercell Slam",
+        "Thunderclap",
+    ],
+    // Psychic
+    &[
+        "Confusion",
+        "Psychic",
+        "Psybeam",
+        "Dream Eater",
+        "Psywave",
+        "Future Sight",
+        "Extrasensory",
+        "Luster Purge",
+        "Mist Ball",
+        "Psycho Boost",
+        "Psycho Cut",
+        "Zen Headbutt",
+        "Psyshock",
+        "Psystrike",
+        "Stored Power",
+        "Synchronoise",
+        "Heart Stamp",
+        "Hyperspace Hole",
+        "Photon Geyser",
+        "Prismatic Laser",
+        "Psychic Fangs",
+        "Expanding Force",
+        "Eerie Spell",
+        "Freezing Glare",
+        "Psyshield Bash",
+        "Mystical Power",
+        "Esper Wing",
+        "Lumina Crash",
+        "Twin Beam",
+        "Psyblade",
+        "Psychic Noise",
+    ],
+    // Ice
+    &[
+        "Ice Beam",
+        "Blizzard",
+        "Ice Punch",
+        "Aurora Beam",
+        "Powder Snow",
+        "Icy Wind",
+        "Sheer Cold",
+        "Ice Ball",
+        "Ice Shard",
+        "Avalanche",
+        "Ice Fang",
+        "Frost Breath",
+        "Icicle Crash",
+        "Icicle Spear",
+        "Freeze Shock",
+        "Ice Burn",
+        "Glaciate",
+        "Freeze-Dry",
+        "Ice Hammer",
+        "Triple Axel",
+        "Glacial Lance",
+        "Ice Spinner",
+        "Mountain Gale",
+    ],
+    // Dragon
+    &[
+        "Dragon Rage",
+        "Twister",
+        "Outrage",
+        "Dragon Breath",
+        "Dragon Claw",
+        "Dragon Pulse",
+        "Dragon Rush",
+        "Draco Meteor",
+        "Roar of Time",
+        "Spacial Rend",
+        "Dual Chop",
+        "Dragon Tail",
+        "Core Enforcer",
+        "Clanging Scales",
+        "Dragon Hammer",
+        "Dynamax Cannon",
+        "Dragon Darts",
+        "Breaking Swipe",
+        "Scale Shot",
+        "Dragon Energy",
+        "Eternabeam",
+        "Glaive Rush",
+        "Order Up",
+        "Fickle Beam",
+    ],
+    // Dark
+    &[
+        "Bite",
+        "Thief",
+        "Feint Attack",
+        "Pursuit",
+        "Crunch",
+        "Beat Up",
+        "Knock Off",
+        "Assurance",
+        "Payback",
+        "Punishment",
+        "Dark Pulse",
+        "Night Slash",
+        "Sucker Punch",
+        "Foul Play",
+        "Snarl",
+        "Night Daze",
+        "Hyperspace Fury",
+        "Throat Chop",
+        "Darkest Lariat",
+        "Power Trip",
+        "Brutal Swing",
+        "Jaw Lock",
+        "False Surrender",
+        "Lash Out",
+        "Wicked Blow",
+        "Fiery Wrath",
+        "Ceaseless Edge",
+        "Kowtow Cleave",
+        "Ruination",
+        "Comeuppance",
+    ],
+    // Fairy
+    &[
+        "Moonblast",
+        "Dazzling Gleam",
+        "Draining Kiss",
+        "Play Rough",
+        "Disarming Voice",
+        "Fairy Wind",
+        "Light of Ruin",
+        "Fleur Cannon",
+        "Spirit Break",
+        "Strange Steam",
+        "Misty Explosion",
+        "Springtide Storm",
+        "Magical Torque",
+        "Alluring Voice",
+    ],
+];
+
+/// Moves that take the user's Tera type once it has Terastallized, Normal before that.
+const TERA_MOVES: [&str; 2] = ["Tera Blast", "Tera Starstorm"];
+
+/// Damaging moves whose type depends on the weather, terrain, held item or the user's form.
+const VARIABLE_MOVES: [&str; 11] = [
+    "Weather Ball",
+    "Terrain Pulse",
+    "Judgment",
+    "Techno Blast",
+    "Multi-Attack",
+    "Revelation Dance",
+    "Natural Gift",
+    "Hidden Power",
+    "Raging Bull",
+    "Ivy Cudgel",
+    "Aura Wheel",
+];
+
+/// How a damaging move's type is decided.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MoveType {
+    Fixed(usize),
+    /// Tera Blast and Tera Starstorm.
+    Tera,
+    /// Depends on more than the move, see [`VARIABLE_MOVES`].
+    Variable,
+}
+
+impl MoveType {
+    /// The type the move hits with for a user with the given Tera type. Raid bosses are always
+    /// Terastallized, so `None` there means a random Tera type and Tera moves are unknown too.
+    pub fn resolve(self, tera_type: Option<usize>) -> Option<usize> {
+        match self {
+            MoveType::Fixed(t) => Some(t),
+            MoveType::Tera => tera_type,
+            MoveType::Variable => None,
+        }
+    }
+
+    /// Like [`MoveType::resolve`] for a player's Pokémon, whose Tera moves stay Normal type
+    /// when it has no Tera type to Terastallize into.
+    pub fn resolve_for_player(self, tera_type: Option<usize>) -> Option<usize> {
+        match self {
+            MoveType::Tera => Some(tera_type.unwrap_or(NORMAL)),
+            kind => kind.resolve(tera_type),
+        }
+    }
+}
+
+struct MoveIndex {
+    /// Indexed by move ID, `None` for status moves and moves missing from the tables.
+    by_id: Vec<Option<MoveType>>,
+    /// Normalized English name to move ID.
+    by_name: HashMap<String, u16>,
+}
+
+impl MoveIndex {
+    fn new() -> Self {
+        let mut kinds = HashMap::new();
+        for (t, moves) in DAMAGING_MOVES.iter().enumerate() {
+            kinds.extend(
+                moves
+                    .iter()
+                    .map(|m| (normalize_move(m), MoveType::Fixed(t))),
+            );
+        }
+        kinds.extend(
+            TERA_MOVES
+                .iter()
+                .map(|m| (normalize_move(m), MoveType::Tera)),
+        );
+        kinds.extend(
+            VARIABLE_MOVES
+                .iter()
+                .map(|m| (normalize_move(m), MoveType::Variable)),
+        );
+
+        let names = MOVES.iter().map(|m| normalize_move(m)).collect::<Vec<_>>();
+        Self {
+            by_id: names.iter().map(|name| kinds.get(name).copied()).collect(),
+            by_name: names
+                .into_iter()
+                .enumerate()
+                .filter(|(_, name)| !name.is_empty())
+                .map(|(id, name)| (name, id as u16))
+                .collect(),
+        }
+    }
+}
+
+/// Built on first use, the name tables are only matched up once.
+static MOVE_INDEX: Mutex<Option<MoveIndex>> = Mutex::new(None);
+
+fn with_move_index<T>(f: impl FnOnce(&MoveIndex) -> T) -> T {
+    let mut index = MOVE_INDEX.lock().unwrap_or_else(|e| e.into_inner());
+    f(index.get_or_insert_with(MoveIndex::new))
+}
+
+fn normalize_move(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// How the type of a damaging move is decided, `None` for status moves and moves missing from
+/// the table.
+pub fn move_kind(move_id: u16) -> Option<MoveType> {
+    with_move_index(|index| index.by_id.get(move_id as usize).copied().flatten())
+}
+
+/// Like [`move_kind`], for move names typed by hand or read from a Showdown set.
+pub fn move_kind_by_name(name: &str) -> Option<MoveType> {
+    let name = normalize_move(name);
+    with_move_index(|index| {
+        let id = *index.by_name.get(&name)?;
+        index.by_id[id as usize]
+    })
+}
+
+/// Type of a damaging move used by a raid boss, which is always Terastallized.
+pub fn boss_move_type(move_id: u16, tera_type: Option<usize>) -> Option<usize> {
+    move_kind(move_id)?.resolve(tera_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listed_moves_are_in_the_move_table() {
+        let names = MOVES.iter().map(|m| normalize_move(m)).collect::<Vec<_>>();
+        let missing = DAMAGING_MOVES
+            .iter()
+            .flat_map(|moves| moves.iter())
+            .chain(TERA_MOVES.iter())
+            .chain(VARIABLE_MOVES.iter())
+            .filter(|m| !names.contains(&normalize_move(m)))
+            .collect::<Vec<_>>();
+        assert!(missing.is_empty(), "not in MOVES: {:?}", missing);
+    }
+
+    #[test]
+    fn resolves_boss_move_types() {
+        const FLAMETHROWER: u16 = 53;
+        const EARTHQUAKE: u16 = 89;
+        const SWORDS_DANCE: u16 = 14;
+        const WEATHER_BALL: u16 = 311;
+        const TERA_BLAST: u16 = 851;
+
+        assert_eq!(boss_move_type(FLAMETHROWER, None), Some(9));
+        assert_eq!(boss_move_type(EARTHQUAKE, Some(9)), Some(4));
+        assert_eq!(boss_move_type(TERA_BLAST, Some(15)), Some(15));
+        assert_eq!(boss_move_type(TERA_BLAST, None), None);
+        assert_eq!(boss_move_type(WEATHER_BALL, Some(9)), None);
+        assert_eq!(boss_move_type(SWORDS_DANCE, Some(9)), None);
+        assert_eq!(boss_move_type(u16::MAX, None), None);
+    }
+
+    #[test]
+    fn resolves_player_move_types_by_name() {
+        let tera_blast = move_kind_by_name("tera blast").unwrap();
+        assert_eq!(tera_blast, MoveType::Tera);
+        assert_eq!(tera_blast.resolve_for_player(None), Some(NORMAL));
+        assert_eq!(tera_blast.resolve_for_player(Some(17)), Some(17));
+        assert_eq!(move_kind_by_name("U-Turn"), Some(MoveType::Fixed(6)));
+        assert_eq!(move_kind_by_name("Swords Dance"), None);
+    }
+
+    #[test]
+    fn chart_has_every_immunity() {
+        let immunities = (0..TYPE_COUNT)
+            .flat_map(|attack| (0..TYPE_COUNT).map(move |defend| (attack, defend)))
+            .filter(|&(attack, defend)| effectiveness(attack, defend) == 0.0)
+            .collect::<Vec<_>>();
+        // Normal and Fighting on Ghost, Poison on Steel, Ground on Flying, Ghost on Normal,
+        // Electric on Ground, Psychic on Dark and Dragon on Fairy.
+        assert_eq!(
+            immunities,
+            [
+                (0, 7),
+                (1, 7),
+                (3, 8),
+                (4, 2),
+                (7, 0),
+                (12, 4),
+                (13, 16),
+                (15, 17)
+            ]
+        );
+    }
+
+    #[test]
+    fn multiplies_dual_types() {
+        assert_eq!(effectiveness_against(14, &[15, 4]), 4.0);
+        assert_eq!(effectiveness_against(10, &[10, 15]), 0.25);
+        assert_eq!(effectiveness_against(9, &[10, 11]), 1.0);
+        assert_eq!(effectiveness_against(4, &[2, 5]), 0.0);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::i18n::tr;
+use eframe::egui::Ui;
+use sv_raid_reader::RaidEncounter;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GameVersion {
+    Scarlet,
+    Violet,
+}
+
+impl GameVersion {
+    pub const ALL: [GameVersion; 2] = [GameVersion::Scarlet, GameVersion::Violet];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameVersion::Scarlet => tr("Scarlet"),
+            GameVersion::Violet => tr("Violet"),
+        }
+    }
+}
+
+/// `rom_ver` is 0 for encounters shared by both games, 1 for Scarlet and 2 for Violet.
+pub fn available_in(encounter: &RaidEncounter, version: GameVersion) -> bool {
+    match encounter.rom_ver {
+        1 => version == GameVersion::Scarlet,
+        2 => version == GameVersion::Violet,
+        _ => true,
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpawnChance {
+    pub version: GameVersion,
+    pub weight: u32,
+    /// Chance in percent of this encounter among all encounters of the same star level.
+    pub chance: f32,
+}
+
+impl SpawnChance {
+    /// Version and weight, without the chance since callers show it relative to different
+    /// things.
+    pub fn label(&self) -> String {
+        format!("{}: {} {}", self.version.name(), tr("weight"), self.weight)
+    }
+}
+
+/// Total spawn weight of the encounters in `pool` at a star level, indexed like
+/// [`GameVersion::ALL`].
+pub fn version_totals(pool: &[RaidEncounter], stars: u8) -> [u32; 2] {
+    GameVersion::ALL.map(|version| {
+        pool.iter()
+            .filter(|e| e.species != 0 && e.difficulty == stars && available_in(e, version))
+            .map(|e| e.rate as u32)
+            .sum()
+    })
+}
+
+/// Spawn weight and chance for each version the encounter is available in, given the
+/// totals from [`version_totals`] for its star level.
+pub fn chances_from_totals(encounter: &RaidEncounter, totals: [u32; 2]) -> Vec<SpawnChance> {
+    GameVersion::ALL
+        .into_iter()
+        .zip(totals)
+        .filter(|&(version, _)| available_in(encounter, version))
+        .map(|(version, total)| {
+            let weight = encounter.rate as u32;
+            SpawnChance {
+                version,
+                weight,
+                chance: if total == 0 {
+                    0.0
+                } else {
+                    weight as f32 * 100.0 / total as f32
+                },
+            }
+        })
+        .collect()
+}
+
+/// Spawn weight and chance for each version the encounter is available in, relative to the
+/// encounters in `pool` that share its star level.
+pub fn spawn_chances(encounter: &RaidEncounter, pool: &[RaidEncounter]) -> Vec<SpawnChance> {
+    chances_from_totals(encounter, version_totals(pool, encounter.difficulty))
+}
+
+/// The chance for the selected version, or the mean of both when no version is selected.
+pub fn version_chance(chances: &[SpawnChance], version: Option<GameVersion>) -> f32 {
+    match version {
+        Some(v) => chances
+            .iter()
+            .find(|c| c.version == v)
+            .map_or(0.0, |c| c.chance),
+        None => chances.iter().map(|c| c.chance).sum::<f32>() / GameVersion::ALL.len() as f32,
+    }
+}
+
+pub fn version_selector(ui: &mut Ui, version: &mut Option<GameVersion>) {
+    ui.radio_value(version, None, tr("Both"));
+    for v in GameVersion::ALL {
+        ui.radio_value(version, Some(v), v.name());
+    }
+}